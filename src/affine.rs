@@ -0,0 +1,209 @@
+use std::ops::Mul;
+
+use crate::{Rect, Size, get_index, stride::Stride};
+
+/// A 2D affine transform: `x' = a * x + c * y + tx` and `y' = b * x + d * y + ty`.
+///
+/// Transforms are combined with `*`: `second * first` applies `first`, then `second`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    /// A transform that doesn't move anything.
+    pub const IDENTITY: Self = Self {
+        a: 1.,
+        b: 0.,
+        c: 0.,
+        d: 1.,
+        tx: 0.,
+        ty: 0.,
+    };
+
+    /// Move by `(x, y)`.
+    pub const fn translation(x: f32, y: f32) -> Self {
+        Self {
+            tx: x,
+            ty: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Scale by `x` horizontally and `y` vertically, about the origin.
+    pub const fn scale(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            d: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotate clockwise by `radians` about the origin. The y axis points down.
+    pub fn rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Shear horizontally by `x` and vertically by `y`, i.e. the point `(px, py)` moves to `(px + x * py, py + y * px)`.
+    pub const fn shear(x: f32, y: f32) -> Self {
+        Self {
+            b: y,
+            c: x,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Transform the point `(x, y)`.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+
+    /// The inverse transform, or `None` if this transform collapses the plane to a line or a point.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0. || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+}
+
+impl Mul for Affine {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            a: self.a * rhs.a + self.c * rhs.b,
+            b: self.b * rhs.a + self.d * rhs.b,
+            c: self.a * rhs.c + self.c * rhs.d,
+            d: self.b * rhs.c + self.d * rhs.d,
+            tx: self.a * rhs.tx + self.c * rhs.ty + self.tx,
+            ty: self.b * rhs.tx + self.d * rhs.ty + self.ty,
+        }
+    }
+}
+
+/// Blit `src` onto `dst` with `transform`, which maps source pixel coordinates to destination pixel coordinates,
+/// e.g. to draw a rotated, scaled, or sheared sprite.
+///
+/// Only the part of `dst` inside `clip_rect`, which is clipped to `dst_size`, is drawn.
+/// Each destination pixel whose center maps back inside `src` is copied from the nearest source pixel.
+/// Nothing is drawn if `transform` isn't invertible.
+pub fn blit_affine(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_size: &Size,
+    stride: Stride,
+    transform: &Affine,
+    clip_rect: &Rect,
+) {
+    let Some(inverse) = transform.inverse() else {
+        return;
+    };
+    if src_size.w == 0 || src_size.h == 0 {
+        return;
+    }
+    // The bounding box of the transformed source, clipped.
+    let (w, h) = (src_size.w as f32, src_size.h as f32);
+    let corners = [(0., 0.), (w, 0.), (0., h), (w, h)].map(|(x, y)| transform.apply(x, y));
+    let (min_x, max_x, min_y, max_y) = corners.iter().fold(
+        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+        |(min_x, max_x, min_y, max_y), (x, y)| {
+            (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y))
+        },
+    );
+    let x0 = (min_x.floor().max(0.) as usize).max(clip_rect.position.x);
+    let y0 = (min_y.floor().max(0.) as usize).max(clip_rect.position.y);
+    let x1 = (max_x.ceil().max(0.) as usize)
+        .min(clip_rect.right())
+        .min(dst_size.w);
+    let y1 = (max_y.ceil().max(0.) as usize)
+        .min(clip_rect.bottom())
+        .min(dst_size.h);
+    (y0..y1).for_each(|y| {
+        (x0..x1).for_each(|x| {
+            let (u, v) = inverse.apply(x as f32 + 0.5, y as f32 + 0.5);
+            if u >= 0. && v >= 0. && u < w && v < h {
+                let src_index = get_index(u as usize, v as usize, src_size.w, stride);
+                let dst_index = get_index(x, y, dst_size.w, stride);
+                dst[dst_index..dst_index + stride.get()]
+                    .copy_from_slice(&src[src_index..src_index + stride.get()]);
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_affine() {
+        // 1 2
+        // 3 4
+        let src = [1, 2, 3, 4];
+        let src_size = Size { w: 2, h: 2 };
+        let dst_size = Size { w: 4, h: 4 };
+        // Rotate a quarter turn clockwise, then move back into view and scale up.
+        let transform = Affine::scale(2., 2.)
+            * Affine::translation(2., 0.)
+            * Affine::rotation(std::f32::consts::FRAC_PI_2);
+        let mut dst = [0; 16];
+        blit_affine(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            GRAYSCALE,
+            &transform,
+            &Rect::new(0, 0, 4, 4),
+        );
+        assert_eq!(dst, [3, 3, 1, 1, 3, 3, 1, 1, 4, 4, 2, 2, 4, 4, 2, 2]);
+
+        // Clip to the top row.
+        let mut dst = [0; 16];
+        blit_affine(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            GRAYSCALE,
+            &transform,
+            &Rect::new(0, 0, 4, 1),
+        );
+        assert_eq!(dst[..4], [3, 3, 1, 1]);
+        assert!(dst[4..].iter().all(|c| *c == 0));
+        assert_eq!(Affine::scale(0., 1.).inverse(), None);
+    }
+}
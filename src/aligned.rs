@@ -0,0 +1,97 @@
+use std::{
+    alloc::{Layout, alloc_zeroed, dealloc, handle_alloc_error},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    slice::{from_raw_parts, from_raw_parts_mut},
+};
+
+use crate::stride::Stride;
+
+/// A zero-initialized byte buffer whose first byte starts at an `align`-byte boundary.
+///
+/// Dereferences to `[u8]`, so it can be passed anywhere a `src` or `dst` slice is expected.
+pub struct AlignedVec {
+    ptr: NonNull<u8>,
+    len: usize,
+    align: usize,
+}
+
+impl AlignedVec {
+    /// The alignment of the buffer in bytes.
+    pub const fn align(&self) -> usize {
+        self.align
+    }
+}
+
+impl Deref for AlignedVec {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedVec {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedVec {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe {
+                dealloc(
+                    self.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(self.len, self.align),
+                )
+            };
+        }
+    }
+}
+
+// `AlignedVec` uniquely owns its allocation, just like `Vec<u8>`.
+unsafe impl Send for AlignedVec {}
+unsafe impl Sync for AlignedVec {}
+
+/// Allocate a zeroed buffer of `len` bytes that starts at an `align`-byte boundary, e.g. 32 for AVX or 64 for AVX-512.
+///
+/// Panics if `align` isn't a power of two.
+pub fn alloc_aligned(len: usize, align: usize) -> AlignedVec {
+    let layout = Layout::from_size_align(len, align).expect("`align` must be a power of two");
+    let ptr = if len == 0 {
+        // Zero-sized allocations are UB, so use a dangling (but aligned) pointer instead.
+        NonNull::new(align as *mut u8).unwrap()
+    } else {
+        match NonNull::new(unsafe { alloc_zeroed(layout) }) {
+            Some(ptr) => ptr,
+            None => handle_alloc_error(layout),
+        }
+    };
+    AlignedVec { ptr, len, align }
+}
+
+/// Returns true if every row of an image `w` pixels wide starts at an `align`-byte boundary,
+/// assuming that the first row does.
+pub const fn rows_are_aligned(w: usize, stride: Stride, align: usize) -> bool {
+    (w * stride.get()).is_multiple_of(align)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGBA;
+
+    #[test]
+    fn test_alloc_aligned() {
+        for align in [16, 32, 64] {
+            let buffer = alloc_aligned(100 * 3 * RGBA, align);
+            assert_eq!(buffer.as_ptr() as usize % align, 0);
+            assert_eq!(buffer.len(), 100 * 3 * RGBA);
+            assert!(buffer.iter().all(|b| *b == 0));
+        }
+        assert!(alloc_aligned(0, 64).is_empty());
+        assert!(rows_are_aligned(16, RGBA, 64));
+        assert!(!rows_are_aligned(15, RGBA, 64));
+    }
+}
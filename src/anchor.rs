@@ -0,0 +1,77 @@
+use crate::{PositionI, Size};
+
+/// The point of a source image that is placed at a target position.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// A pivot `(x, y)` pixels from the source image's top-left corner.
+    Custom(usize, usize),
+}
+
+impl Anchor {
+    /// The anchor's offset in pixels from the top-left corner of an image of size `size`.
+    ///
+    /// Centers are rounded down, e.g. the center of a 5-pixel-wide image is at x=2.
+    pub const fn offset(&self, size: &Size) -> (usize, usize) {
+        let (w, h) = (size.w, size.h);
+        match self {
+            Self::TopLeft => (0, 0),
+            Self::TopCenter => (w / 2, 0),
+            Self::TopRight => (w, 0),
+            Self::CenterLeft => (0, h / 2),
+            Self::Center => (w / 2, h / 2),
+            Self::CenterRight => (w, h / 2),
+            Self::BottomLeft => (0, h),
+            Self::BottomCenter => (w / 2, h),
+            Self::BottomRight => (w, h),
+            Self::Custom(x, y) => (*x, *y),
+        }
+    }
+}
+
+/// Returns the top-left position at which to blit an image of size `src_size` such that its `anchor` is at `target`.
+///
+/// The result may be negative, so pass it to [`crate::clip`] before blitting.
+pub const fn resolve_position(anchor: Anchor, src_size: &Size, target: &PositionI) -> PositionI {
+    let (x, y) = anchor.offset(src_size);
+    PositionI {
+        x: target.x - x.cast_signed(),
+        y: target.y - y.cast_signed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_position() {
+        let src_size = Size { w: 5, h: 4 };
+        let target = PositionI { x: 10, y: 1 };
+        assert_eq!(
+            resolve_position(Anchor::TopLeft, &src_size, &target),
+            target
+        );
+        assert_eq!(
+            resolve_position(Anchor::Center, &src_size, &target),
+            PositionI { x: 8, y: -1 }
+        );
+        assert_eq!(
+            resolve_position(Anchor::BottomRight, &src_size, &target),
+            PositionI { x: 5, y: -3 }
+        );
+        assert_eq!(
+            resolve_position(Anchor::Custom(1, 3), &src_size, &target),
+            PositionI { x: 9, y: -2 }
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Per-pixel blending math shared by the blending blit functions.
+
+/// Returns `a * b / 255`, rounded to the nearest integer.
+pub(crate) const fn mul_div_255(a: u8, b: u8) -> u8 {
+    let x = a as u32 * b as u32 + 128;
+    ((x + (x >> 8)) >> 8) as u8
+}
+
+/// Composite the straight-alpha RGBA pixel `src` over `dst` using source-over blending, with `src`'s alpha first scaled by `coverage`.
+#[inline]
+pub(crate) fn source_over(src: &[u8], dst: &mut [u8], coverage: u8) {
+    let sa = mul_div_255(src[3], coverage);
+    match sa {
+        0 => {}
+        255 => dst[..4].copy_from_slice(&src[..4]),
+        _ => {
+            let da = dst[3];
+            if da == 255 {
+                // Opaque destination: this is a simple lerp.
+                (0..3).for_each(|i| dst[i] = lerp(dst[i], src[i], sa));
+            } else {
+                // out_a = sa + da * (1 - sa)
+                let dst_weight = mul_div_255(da, 255 - sa) as u32;
+                let out_a = sa as u32 + dst_weight;
+                (0..3).for_each(|i| {
+                    let c = src[i] as u32 * sa as u32 + dst[i] as u32 * dst_weight;
+                    dst[i] = ((c + out_a / 2) / out_a) as u8;
+                });
+                dst[3] = out_a as u8;
+            }
+        }
+    }
+}
+
+/// Linearly interpolate from `a` to `b` by `t / 255`.
+#[inline]
+pub(crate) const fn lerp(a: u8, b: u8, t: u8) -> u8 {
+    let x = a as u32 * (255 - t) as u32 + b as u32 * t as u32 + 128;
+    ((x + (x >> 8)) >> 8) as u8
+}
+
+/// Converts `alpha` from `[0, 1]` to `[0, 255]`.
+pub(crate) fn alpha_to_u8(alpha: f32) -> u8 {
+    (alpha.clamp(0., 1.) * 255. + 0.5) as u8
+}
+
+/// Blend each byte of `prev` towards `curr` by `t / 255` and write it to `dst`.
+pub(crate) fn blend_bytes(prev: &[u8], curr: &[u8], t: u8, dst: &mut [u8]) {
+    dst.iter_mut()
+        .zip(prev.iter().zip(curr))
+        .for_each(|(d, (a, b))| *d = lerp(*a, *b, t));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_255() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let expected = ((a as f32 * b as f32) / 255.).round() as u8;
+                assert_eq!(mul_div_255(a, b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_source_over() {
+        let mut dst = [0, 0, 255, 255];
+        source_over(&[255, 0, 0, 255], &mut dst, 128);
+        assert_eq!(dst, [128, 0, 127, 255]);
+
+        let mut dst = [0, 0, 0, 0];
+        source_over(&[255, 0, 0, 128], &mut dst, 255);
+        assert_eq!(dst, [255, 0, 0, 128]);
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+
+use crate::{PositionU, Size, blit, stride::Stride};
+
+/// An error returned by [`try_blit`] when the sizes don't match the buffers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlitError {
+    /// `src` is `len` bytes long but `src_size` needs `expected` bytes.
+    SrcTooSmall { len: usize, expected: usize },
+    /// `dst` is `len` bytes long but `dst_size` needs `expected` bytes.
+    DstTooSmall { len: usize, expected: usize },
+    /// The source image, placed at the destination position, doesn't fit within the destination image. See [`crate::clip`].
+    OutOfBounds,
+    /// The length in bytes of the source or destination image overflows `usize`.
+    SizeOverflow,
+    /// The number of threads is zero.
+    ZeroThreads,
+}
+
+impl fmt::Display for BlitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SrcTooSmall { len, expected } => write!(
+                f,
+                "The source buffer is {len} bytes but its size needs {expected} bytes"
+            ),
+            Self::DstTooSmall { len, expected } => write!(
+                f,
+                "The destination buffer is {len} bytes but its size needs {expected} bytes"
+            ),
+            Self::OutOfBounds => write!(f, "The source image is out of the destination's bounds"),
+            Self::SizeOverflow => write!(f, "The length of an image in bytes overflows usize"),
+            Self::ZeroThreads => write!(f, "The number of threads is zero"),
+        }
+    }
+}
+
+impl std::error::Error for BlitError {}
+
+/// Check that a blit with these arguments won't panic.
+pub(crate) fn validate(
+    src: &[u8],
+    src_size: &Size,
+    dst: &[u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) -> Result<(), BlitError> {
+    let expected = src_size
+        .checked_len(stride)
+        .ok_or(BlitError::SizeOverflow)?;
+    if src.len() < expected {
+        return Err(BlitError::SrcTooSmall {
+            len: src.len(),
+            expected,
+        });
+    }
+    let expected = dst_size
+        .checked_len(stride)
+        .ok_or(BlitError::SizeOverflow)?;
+    if dst.len() < expected {
+        return Err(BlitError::DstTooSmall {
+            len: dst.len(),
+            expected,
+        });
+    }
+    let fits = |position: usize, src: usize, dst: usize| {
+        position.checked_add(src).is_some_and(|end| end <= dst)
+    };
+    if src_size.w > 0
+        && src_size.h > 0
+        && !(fits(dst_position.x, src_size.w, dst_size.w)
+            && fits(dst_position.y, src_size.h, dst_size.h))
+    {
+        return Err(BlitError::OutOfBounds);
+    }
+    Ok(())
+}
+
+/// Blit `src` onto `dst` like [`blit`], but return an error instead of panicking if the sizes are inconsistent, e.g. for untrusted input.
+pub fn try_blit(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) -> Result<(), BlitError> {
+    validate(src, src_size, dst, dst_position, dst_size, stride)?;
+    blit(src, src_size, dst, dst_position, dst_size, stride);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGB};
+
+    #[test]
+    fn test_try_blit() {
+        let src = [1u8; 2 * 2 * RGB.get()];
+        let src_size = Size { w: 2, h: 2 };
+        let mut dst = [0u8; 3 * 3 * RGB.get()];
+        let dst_size = Size { w: 3, h: 3 };
+        let position = PositionU { x: 1, y: 1 };
+        assert!(try_blit(&src, &src_size, &mut dst, &position, &dst_size, RGB).is_ok());
+        assert_eq!(
+            try_blit(&src[1..], &src_size, &mut dst, &position, &dst_size, RGB),
+            Err(BlitError::SrcTooSmall {
+                len: 11,
+                expected: 12
+            })
+        );
+        assert_eq!(
+            try_blit(&src, &src_size, &mut dst[1..], &position, &dst_size, RGB),
+            Err(BlitError::DstTooSmall {
+                len: 26,
+                expected: 27
+            })
+        );
+        assert_eq!(
+            try_blit(
+                &src,
+                &src_size,
+                &mut dst,
+                &PositionU { x: 2, y: 0 },
+                &dst_size,
+                RGB
+            ),
+            Err(BlitError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_try_blit_overflow() {
+        let src = [0u8; 12];
+        let mut dst = [0u8; 12];
+        let size = Size { w: 2, h: 2 };
+        assert_eq!(
+            try_blit(
+                &src,
+                &Size {
+                    w: usize::MAX / 2,
+                    h: 3
+                },
+                &mut dst,
+                &PositionU::default(),
+                &size,
+                RGB
+            ),
+            Err(BlitError::SizeOverflow)
+        );
+        // This would wrap to 0 bytes.
+        assert_eq!(
+            try_blit(
+                &src,
+                &size,
+                &mut dst,
+                &PositionU::default(),
+                &Size {
+                    w: 1 << (usize::BITS - 1),
+                    h: 2
+                },
+                GRAYSCALE
+            ),
+            Err(BlitError::SizeOverflow)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    PositionU, Size,
+    blend::lerp,
+    get_index,
+    stride::{RGB, RGBA},
+};
+
+/// The light and dark gray of a typical image editor's transparency checkerboard.
+pub const CHECKER_COLORS: [[u8; 3]; 2] = [[204, 204, 204], [255, 255, 255]];
+
+/// Blit an RGBA `src` onto an RGB `dst` as if `src` were drawn over a transparency checkerboard, in one pass.
+///
+/// - `cell_size` is the width and height of each checkerboard square in pixels.
+/// - `colors` are the two alternating checkerboard colors. See [`CHECKER_COLORS`].
+///
+/// The checkerboard is anchored to the origin of `dst`.
+#[allow(clippy::too_many_arguments)]
+pub fn composite_over_checker(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    cell_size: usize,
+    colors: &[[u8; 3]; 2],
+) {
+    if src_size.w > 0 && src_size.h > 0 {
+        let cell_size = cell_size.max(1);
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, RGBA);
+            let y = dst_position.y + src_y;
+            let dst_index = get_index(dst_position.x, y, dst_size.w, RGB);
+            src[src_index..src_index + src_size.w * RGBA]
+                .chunks_exact(RGBA.get())
+                .zip(dst[dst_index..dst_index + src_size.w * RGB].chunks_exact_mut(RGB.get()))
+                .enumerate()
+                .for_each(|(src_x, (src, dst))| {
+                    let x = dst_position.x + src_x;
+                    let checker = &colors[(x / cell_size + y / cell_size) % 2];
+                    (0..3).for_each(|i| dst[i] = lerp(checker[i], src[i], src[3]));
+                });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_over_checker() {
+        let src = [
+            0, 0, 0, 0, 0, 0, 0, 0, //
+            0, 0, 0, 255, 0, 0, 0, 128,
+        ];
+        let src_size = Size { w: 2, h: 2 };
+        let mut dst = [0u8; 2 * 2 * RGB.get()];
+        composite_over_checker(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            1,
+            &CHECKER_COLORS,
+        );
+        assert_eq!(dst, [204, 204, 204, 255, 255, 255, 0, 0, 0, 102, 102, 102]);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use crate::{PositionI, Size, stride::Stride};
+
+/// An unbounded image made of fixed-size chunks, for infinite-world editors and paint programs.
+///
+/// Positions are in world pixels and can be negative.
+/// Chunks are allocated (zeroed) the first time they're drawn to, so untouched areas cost nothing.
+pub struct ChunkedSurface {
+    chunk_size: Size,
+    stride: Stride,
+    chunks: HashMap<(isize, isize), Vec<u8>>,
+}
+
+impl ChunkedSurface {
+    /// Create an empty surface of `chunk_size` chunks.
+    ///
+    /// Panics if the chunk width or height is zero.
+    pub fn new(chunk_size: Size, stride: Stride) -> Self {
+        assert!(
+            chunk_size.w > 0 && chunk_size.h > 0,
+            "The chunk size can't be zero"
+        );
+        Self {
+            chunk_size,
+            stride,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// The size of each chunk in pixels.
+    pub const fn chunk_size(&self) -> Size {
+        self.chunk_size
+    }
+
+    /// The number of allocated chunks.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// The `(x, y)` cell of the chunk that contains the world pixel `position`, and the pixel's offset within the chunk.
+    pub const fn cell(&self, position: &PositionI) -> ((isize, isize), (usize, usize)) {
+        cell(self.chunk_size, position)
+    }
+
+    /// The pixels of the chunk at `cell`, or `None` if it hasn't been drawn to.
+    pub fn chunk(&self, cell: (isize, isize)) -> Option<&[u8]> {
+        self.chunks.get(&cell).map(|chunk| chunk.as_slice())
+    }
+
+    /// Blit `src`, an image of size `src_size`, at the world pixel `position`.
+    /// The blit is split across every chunk it overlaps, allocating chunks as needed.
+    pub fn blit(&mut self, src: &[u8], src_size: &Size, position: &PositionI) {
+        let row_len = src_size.w * self.stride;
+        let (chunk_w, chunk_h) = (self.chunk_size.w, self.chunk_size.h);
+        let stride = self.stride;
+        for_each_span(
+            self.chunk_size,
+            position,
+            src_size,
+            |cell, (x, y), (src_x, src_y), w| {
+                let chunk = self
+                    .chunks
+                    .entry(cell)
+                    .or_insert_with(|| vec![0; chunk_w * chunk_h * stride]);
+                let src_index = src_y * row_len + src_x * stride;
+                let dst_index = (x + y * chunk_w) * stride;
+                chunk[dst_index..dst_index + w * stride]
+                    .copy_from_slice(&src[src_index..src_index + w * stride]);
+            },
+        );
+    }
+
+    /// Copy the area of size `dst_size` at the world pixel `position` into `dst`.
+    /// Pixels in unallocated chunks are zero.
+    pub fn read(&self, dst: &mut [u8], dst_size: &Size, position: &PositionI) {
+        let row_len = dst_size.w * self.stride;
+        let chunk_w = self.chunk_size.w;
+        let stride = self.stride;
+        for_each_span(
+            self.chunk_size,
+            position,
+            dst_size,
+            |cell, (x, y), (dst_x, dst_y), w| {
+                let dst_index = dst_y * row_len + dst_x * stride;
+                let dst = &mut dst[dst_index..dst_index + w * stride];
+                match self.chunks.get(&cell) {
+                    Some(chunk) => {
+                        let src_index = (x + y * chunk_w) * stride;
+                        dst.copy_from_slice(&chunk[src_index..src_index + w * stride]);
+                    }
+                    None => dst.fill(0),
+                }
+            },
+        );
+    }
+}
+
+const fn cell(chunk_size: Size, position: &PositionI) -> ((isize, isize), (usize, usize)) {
+    let (w, h) = (chunk_size.w.cast_signed(), chunk_size.h.cast_signed());
+    (
+        (position.x.div_euclid(w), position.y.div_euclid(h)),
+        (
+            position.x.rem_euclid(w).cast_unsigned(),
+            position.y.rem_euclid(h).cast_unsigned(),
+        ),
+    )
+}
+
+/// Call `f` for every run of pixels where an area of `size` at world `position` overlaps a chunk row.
+///
+/// `f` receives the chunk cell, the start of the run within the chunk, the start of the run within the area, and the run's width.
+fn for_each_span<F: FnMut((isize, isize), (usize, usize), (usize, usize), usize)>(
+    chunk_size: Size,
+    position: &PositionI,
+    size: &Size,
+    mut f: F,
+) {
+    (0..size.h).for_each(|area_y| {
+        let mut area_x = 0;
+        while area_x < size.w {
+            let world = PositionI {
+                x: position.x + area_x.cast_signed(),
+                y: position.y + area_y.cast_signed(),
+            };
+            let (cell, (x, y)) = cell(chunk_size, &world);
+            let w = (chunk_size.w - x).min(size.w - area_x);
+            f(cell, (x, y), (area_x, area_y), w);
+            area_x += w;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_chunked_surface() {
+        let mut surface = ChunkedSurface::new(Size { w: 4, h: 4 }, GRAYSCALE);
+        let src_size = Size { w: 3, h: 2 };
+        let src = [1, 2, 3, 4, 5, 6];
+        // Straddle four chunks around the origin.
+        let position = PositionI { x: -1, y: -1 };
+        surface.blit(&src, &src_size, &position);
+        assert_eq!(surface.chunk_count(), 4);
+        assert_eq!(surface.chunk((-1, -1)).unwrap()[15], 1);
+        assert_eq!(surface.chunk((0, -1)).unwrap()[12..14], [2, 3]);
+        assert_eq!(surface.chunk((-1, 0)).unwrap()[3], 4);
+        assert_eq!(surface.chunk((0, 0)).unwrap()[0..3], [5, 6, 0]);
+
+        let mut dst = [9; 8];
+        surface.read(&mut dst, &Size { w: 4, h: 2 }, &position);
+        assert_eq!(dst, [1, 2, 3, 0, 4, 5, 6, 0]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Conversion between 8-bit sRGB and linear light.
+//!
+//! The lookup tables are built the first time they're used and shared after that.
+//! In a hot loop, get a table once with [`decode_lut`] or [`encode_lut`] instead of calling [`decode`] or [`encode`] per channel.
+
+use std::sync::OnceLock;
+
+use crate::stride::Stride;
+
+/// The number of entries in [`encode_lut`]. Linear values are quantized to this many steps before they're encoded.
+pub const ENCODE_LEN: usize = 4096;
+
+static DECODE: OnceLock<[f32; 256]> = OnceLock::new();
+static ENCODE: OnceLock<Box<[u8; ENCODE_LEN]>> = OnceLock::new();
+
+/// Decode an sRGB value in `[0, 1]` to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear value in `[0, 1]` as sRGB.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
+
+/// A table that maps each 8-bit sRGB value to linear light in `[0, 1]`.
+pub fn decode_lut() -> &'static [f32; 256] {
+    DECODE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.)))
+}
+
+/// A table that maps the linear value `i / (ENCODE_LEN - 1)` to 8-bit sRGB.
+pub fn encode_lut() -> &'static [u8; ENCODE_LEN] {
+    ENCODE.get_or_init(|| {
+        let mut lut = Box::new([0; ENCODE_LEN]);
+        lut.iter_mut().enumerate().for_each(|(i, value)| {
+            *value = (linear_to_srgb(i as f32 / (ENCODE_LEN - 1) as f32) * 255. + 0.5) as u8;
+        });
+        lut
+    })
+}
+
+/// Decode the 8-bit sRGB value `c` to linear light with [`decode_lut`].
+#[inline]
+pub fn decode(c: u8) -> f32 {
+    decode_lut()[c as usize]
+}
+
+/// Encode the linear value `c` as 8-bit sRGB with [`encode_lut`]. `c` is clamped to `[0, 1]`.
+#[inline]
+pub fn encode(c: f32) -> u8 {
+    encode_lut()[encode_index(c)]
+}
+
+/// Returns the index in [`encode_lut`] of the linear value `c`, clamped to `[0, 1]`.
+#[inline]
+pub fn encode_index(c: f32) -> usize {
+    (c.clamp(0., 1.) * (ENCODE_LEN - 1) as f32 + 0.5) as usize
+}
+
+/// Decode the 8-bit sRGB pixels in `src` to linear f32 pixels in `dst`.
+///
+/// `stride` is the per-pixel stride of `src`, which is also the number of channels per pixel in `dst`.
+/// If it's 2 or 4, the last channel is alpha, which is scaled to `[0, 1]` but not decoded.
+pub fn srgb_to_linear_slice(src: &[u8], dst: &mut [f32], stride: Stride) {
+    let lut = decode_lut();
+    let colors = color_channels(stride);
+    src.chunks_exact(stride.get())
+        .zip(dst.chunks_exact_mut(stride.get()))
+        .for_each(|(src, dst)| {
+            dst.iter_mut().zip(src).enumerate().for_each(|(i, (d, s))| {
+                *d = if i < colors {
+                    lut[*s as usize]
+                } else {
+                    *s as f32 / 255.
+                }
+            })
+        });
+}
+
+/// Encode the linear f32 pixels in `src`, clamped to `[0, 1]`, as 8-bit sRGB pixels in `dst`.
+///
+/// `stride` is the per-pixel stride of `dst`, which is also the number of channels per pixel in `src`.
+/// If it's 2 or 4, the last channel is alpha, which is scaled to `[0, 255]` but not encoded.
+pub fn linear_to_srgb_slice(src: &[f32], dst: &mut [u8], stride: Stride) {
+    let lut = encode_lut();
+    let colors = color_channels(stride);
+    src.chunks_exact(stride.get())
+        .zip(dst.chunks_exact_mut(stride.get()))
+        .for_each(|(src, dst)| {
+            dst.iter_mut().zip(src).enumerate().for_each(|(i, (d, s))| {
+                *d = if i < colors {
+                    lut[encode_index(*s)]
+                } else {
+                    (s.clamp(0., 1.) * 255. + 0.5) as u8
+                }
+            })
+        });
+}
+
+/// The number of color channels in a pixel, i.e. the stride without the alpha channel, if any.
+const fn color_channels(stride: Stride) -> usize {
+    match stride.get() {
+        2 | 4 => stride.get() - 1,
+        n => n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGBA;
+
+    #[test]
+    fn test_color() {
+        (0..=255u8).for_each(|c| assert_eq!(encode(decode(c)), c));
+        let src = [0, 128, 255, 128];
+        let mut linear = [0.; 4];
+        srgb_to_linear_slice(&src, &mut linear, RGBA);
+        assert!((linear[1] - 0.2158).abs() < 0.001);
+        assert_eq!(linear[3], 128. / 255.);
+        let mut dst = [0; 4];
+        linear_to_srgb_slice(&linear, &mut dst, RGBA);
+        assert_eq!(dst, src);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    ColorStop, PositionU, Size, get_index,
+    gradient::gradient_lut,
+    stride::{GRAYSCALE, Stride},
+};
+
+/// A false-color map from a single-channel value to an RGB color.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ColorMap {
+    /// Matplotlib's perceptually uniform dark blue → green → yellow map.
+    #[default]
+    Viridis,
+    /// Matplotlib's perceptually uniform black → purple → pale yellow map.
+    Magma,
+    /// Google's rainbow-like dark blue → green → dark red map.
+    Turbo,
+    /// A user-defined gradient. See [`crate::apply_gradient_map`].
+    Custom(Vec<ColorStop>),
+}
+
+impl ColorMap {
+    /// Sample the map at 256 evenly spaced positions.
+    fn lut(&self) -> [[u8; 3]; 256] {
+        match self {
+            Self::Viridis => polynomial_lut(&VIRIDIS),
+            Self::Magma => polynomial_lut(&MAGMA),
+            Self::Turbo => polynomial_lut(&TURBO),
+            Self::Custom(stops) if stops.is_empty() => [[0; 3]; 256],
+            Self::Custom(stops) => gradient_lut(stops),
+        }
+    }
+}
+
+/// Blit a grayscale `src` onto `dst`, coloring each value with `color_map`.
+///
+/// - `src` is a flat byte slice of a [`crate::stride::GRAYSCALE`] image, e.g. a depth buffer or a spectrogram.
+/// - `dst_stride` is the per-pixel stride of `dst` and must be at least 3. The first three channels are written as RGB and any others, such as alpha, are left unchanged.
+///
+/// If `dst_stride` is less than 3, `dst` is unchanged.
+pub fn colorize(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    dst_stride: Stride,
+    color_map: &ColorMap,
+) {
+    if dst_stride.get() < 3 {
+        return;
+    }
+    let lut = color_map.lut();
+    colorize_rows(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        dst_stride,
+        |value| lut[*value as usize],
+    );
+}
+
+/// Blit a single-channel f32 `src` onto `dst`, coloring each value with `color_map`.
+///
+/// - `src` is a flat slice of f32 values. Values are normalized from `[min, max]` to `[0, 1]` and clamped. NaN is treated as `min`.
+/// - `dst_stride` is the per-pixel stride of `dst` and must be at least 3. The first three channels are written as RGB and any others, such as alpha, are left unchanged.
+///
+/// If `dst_stride` is less than 3, `dst` is unchanged.
+#[allow(clippy::too_many_arguments)]
+pub fn colorize_f32(
+    src: &[f32],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    dst_stride: Stride,
+    color_map: &ColorMap,
+    min: f32,
+    max: f32,
+) {
+    if dst_stride.get() < 3 {
+        return;
+    }
+    let lut = color_map.lut();
+    let scale = if max > min { 255. / (max - min) } else { 0. };
+    colorize_rows(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        dst_stride,
+        |value| {
+            // `as` saturates, and maps NaN to 0.
+            lut[((value - min) * scale).clamp(0., 255.).round() as usize]
+        },
+    );
+}
+
+fn colorize_rows<T, F: Fn(&T) -> [u8; 3]>(
+    src: &[T],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    dst_stride: Stride,
+    f: F,
+) {
+    if src_size.w > 0 && src_size.h > 0 && dst_stride.get() >= 3 {
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, GRAYSCALE);
+            let dst_index = get_index(
+                dst_position.x,
+                dst_position.y + src_y,
+                dst_size.w,
+                dst_stride,
+            );
+            src[src_index..src_index + src_size.w]
+                .iter()
+                .zip(
+                    dst[dst_index..dst_index + src_size.w * dst_stride]
+                        .chunks_exact_mut(dst_stride.get()),
+                )
+                .for_each(|(value, pixel)| pixel[..3].copy_from_slice(&f(value)));
+        });
+    }
+}
+
+/// Per-channel polynomial coefficients, lowest degree first.
+type Polynomial = [[f32; 3]; 7];
+
+/// Polynomial fits of the color maps by Matt Zucker (viridis, magma) and Anton Mikhailov (turbo).
+const VIRIDIS: Polynomial = [
+    [0.277_727_33, 0.005_407_344_5, 0.334_099_8],
+    [0.105_093_04, 1.404_613_5, 1.384_590_2],
+    [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+    [-4.634_230_5, -5.799_101, -19.332_441],
+    [6.228_27, 14.179_933, 56.690_55],
+    [4.776_385, -13.745_145, -65.353_03],
+    [-5.435_456, 4.645_852_6, 26.312_435],
+];
+const MAGMA: Polynomial = [
+    [-0.002_136_485, -0.000_749_655_05, -0.005_386_128],
+    [0.251_660_54, 0.677_523_2, 2.494_026_7],
+    [8.353_717, -3.577_719_4, 0.314_467_9],
+    [-27.668_733, 14.264_731, -13.649_213],
+    [52.176_14, -27.943_607, 12.944_169],
+    [-50.768_524, 29.046_583, 4.234_153],
+    [18.655_705, -11.489_774, -5.601_961_5],
+];
+const TURBO: Polynomial = [
+    [0.135_721_38, 0.091_402_61, 0.106_673_3],
+    [4.615_392_6, 2.194_188_4, 12.641_946],
+    [-42.660_324, 4.842_966_6, -60.582_047],
+    [132.131_08, -14.185_033, 110.362_77],
+    [-152.942_4, 4.277_298_5, -89.903_11],
+    [59.286_38, 2.829_566, 27.348_25],
+    [0., 0., 0.],
+];
+
+fn polynomial_lut(polynomial: &Polynomial) -> [[u8; 3]; 256] {
+    std::array::from_fn(|i| {
+        let t = i as f32 / 255.;
+        std::array::from_fn(|c| {
+            // Horner's method.
+            let value = polynomial
+                .iter()
+                .rev()
+                .fold(0., |acc, coefficients| acc * t + coefficients[c]);
+            (value.clamp(0., 1.) * 255.).round() as u8
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_colorize() {
+        let src = [0u8, 255];
+        let src_size = Size { w: 2, h: 1 };
+        let mut dst = [0u8; 2 * RGB.get()];
+        colorize(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            RGB,
+            &ColorMap::Viridis,
+        );
+        // Viridis starts dark purple and ends yellow.
+        assert!(dst[2] > dst[0] && dst[2] > dst[1]);
+        assert!(dst[3] > 200 && dst[4] > 200 && dst[5] < 100);
+
+        colorize_f32(
+            &[-1., f32::NAN],
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            RGB,
+            &ColorMap::Custom(vec![
+                ColorStop {
+                    position: 0.,
+                    color: [1, 2, 3],
+                },
+                ColorStop {
+                    position: 1.,
+                    color: [4, 5, 6],
+                },
+            ]),
+            0.,
+            10.,
+        );
+        assert_eq!(dst, [1, 2, 3, 1, 2, 3]);
+
+        // Grayscale pixels don't have three channels.
+        let mut dst = [7; 2];
+        colorize(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            GRAYSCALE,
+            &ColorMap::Viridis,
+        );
+        assert_eq!(dst, [7; 2]);
+    }
+}
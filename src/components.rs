@@ -0,0 +1,89 @@
+use crate::{Rect, Size};
+
+/// Statistics about one connected component found by [`label_components`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ComponentStats {
+    /// The component's label in the label map. Labels start at 1.
+    pub label: u32,
+    /// The number of pixels in the component.
+    pub area: usize,
+    /// The smallest rect that contains the component.
+    pub rect: Rect,
+}
+
+/// Label the 8-connected regions of non-zero pixels in `mask`, a grayscale image (one byte per pixel) of size `size`.
+///
+/// Returns a label map with one label per pixel, where 0 is the background, and the statistics of each component in label order.
+/// Components are labeled in the order that their top-left-most pixel appears, scanning left to right, top to bottom.
+pub fn label_components(mask: &[u8], size: &Size) -> (Vec<u32>, Vec<ComponentStats>) {
+    let (w, h) = (size.w, size.h);
+    let mut labels = vec![0u32; w * h];
+    let mut stats = Vec::new();
+    let mut stack = Vec::new();
+    (0..w * h).for_each(|start| {
+        if mask[start] == 0 || labels[start] != 0 {
+            return;
+        }
+        let label = stats.len() as u32 + 1;
+        let (mut x0, mut y0, mut x1, mut y1) = (w, h, 0, 0);
+        let mut area = 0;
+        labels[start] = label;
+        stack.push(start);
+        // Flood fill.
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i % w, i / w);
+            area += 1;
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+            (y.saturating_sub(1)..(y + 2).min(h)).for_each(|ny| {
+                (x.saturating_sub(1)..(x + 2).min(w)).for_each(|nx| {
+                    let n = nx + ny * w;
+                    if mask[n] != 0 && labels[n] == 0 {
+                        labels[n] = label;
+                        stack.push(n);
+                    }
+                });
+            });
+        }
+        stats.push(ComponentStats {
+            label,
+            area,
+            rect: Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1),
+        });
+    });
+    (labels, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_components() {
+        #[rustfmt::skip]
+        let mask = [
+            1, 1, 0, 0, 0,
+            0, 0, 1, 0, 1,
+            0, 0, 0, 0, 1,
+        ];
+        let (labels, stats) = label_components(&mask, &Size { w: 5, h: 3 });
+        assert_eq!(labels, [1, 1, 0, 0, 0, 0, 0, 1, 0, 2, 0, 0, 0, 0, 2]);
+        assert_eq!(
+            stats,
+            [
+                ComponentStats {
+                    label: 1,
+                    area: 3,
+                    rect: Rect::new(0, 0, 3, 2)
+                },
+                ComponentStats {
+                    label: 2,
+                    area: 2,
+                    rect: Rect::new(4, 1, 1, 2)
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{
+    BlendMode, PositionU, Size,
+    blend::{lerp, mul_div_255, source_over},
+    blit_mode, blit_rows_with,
+    perf::{self, Strategy},
+    stride::RGBA,
+};
+
+/// Blit the RGBA image `src` onto the RGBA image `dst` using source-over alpha blending, e.g. to draw a sprite.
+///
+/// This is the same as [`crate::blit`] except that pixels are blended instead of copied, so the stride is always [`RGBA`].
+/// Alpha is straight (not premultiplied). Fully opaque and fully transparent source pixels take a fast path.
+///
+/// `opacity` scales the alpha of every source pixel, e.g. to fade a sprite in or out. 255 is fully opaque.
+pub fn blit_blend(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    opacity: u8,
+) {
+    blit_mode(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        RGBA,
+        &SourceOver { opacity },
+    );
+}
+
+/// Source-over blending with the alpha of every source pixel scaled by `opacity`.
+struct SourceOver {
+    opacity: u8,
+}
+
+impl BlendMode for SourceOver {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        source_over(src, dst, self.opacity);
+    }
+}
+
+/// [`CompositeOp::apply_with_opacity`] with a fixed operator and opacity.
+struct WithOpacity {
+    op: CompositeOp,
+    opacity: u8,
+}
+
+impl BlendMode for WithOpacity {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        self.op.apply_with_opacity(src, dst, self.opacity);
+    }
+}
+
+/// A compositing operator, which determines how a source pixel and a destination pixel are combined.
+///
+/// Most operators are Porter-Duff operators, which weight the premultiplied source by `Fa` and the destination by `Fb`,
+/// where `as` and `ad` are the source and destination alphas.
+/// The rest are blend modes that combine each byte of the source and destination, including alpha.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CompositeOp {
+    /// Neither: `Fa = 0, Fb = 0`.
+    Clear,
+    /// Only the source: `Fa = 1, Fb = 0`.
+    Src,
+    /// Only the destination: `Fa = 0, Fb = 1`.
+    Dst,
+    /// The source over the destination: `Fa = 1, Fb = 1 - as`. This is the same as [`blit_blend`].
+    #[default]
+    SrcOver,
+    /// The destination over the source: `Fa = 1 - ad, Fb = 1`.
+    DstOver,
+    /// The source where the destination is opaque: `Fa = ad, Fb = 0`.
+    SrcIn,
+    /// The destination where the source is opaque: `Fa = 0, Fb = as`.
+    DstIn,
+    /// The source where the destination is transparent: `Fa = 1 - ad, Fb = 0`.
+    SrcOut,
+    /// The destination where the source is transparent: `Fa = 0, Fb = 1 - as`.
+    DstOut,
+    /// The source over the destination, only where the destination is opaque: `Fa = ad, Fb = 1 - as`.
+    SrcAtop,
+    /// The destination over the source, only where the source is opaque: `Fa = 1 - ad, Fb = as`.
+    DstAtop,
+    /// The source and the destination where they don't overlap: `Fa = 1 - ad, Fb = 1 - as`.
+    Xor,
+    /// The sum of the source and the destination, clamped: `Fa = 1, Fb = 1`.
+    Plus,
+    /// Additive blending, e.g. for particles, glows, and light maps: `dst = saturating_add(dst, src)` for each byte.
+    Add,
+    /// Multiply blending, e.g. for shadows: `dst = dst * src / 255` for each byte.
+    Multiply,
+    /// Screen blending, e.g. for light overlays: `dst = 255 - (255 - dst) * (255 - src) / 255` for each byte.
+    Screen,
+}
+
+impl CompositeOp {
+    /// Composite the straight-alpha RGBA pixel `src` with `dst`, writing the result to `dst`.
+    pub fn apply(&self, src: &[u8], dst: &mut [u8]) {
+        match self {
+            Self::SrcOver => {
+                source_over(src, dst, 255);
+                return;
+            }
+            Self::Add => {
+                add(&src[..4], &mut dst[..4]);
+                return;
+            }
+            Self::Multiply => {
+                multiply(&src[..4], &mut dst[..4]);
+                return;
+            }
+            Self::Screen => {
+                screen(&src[..4], &mut dst[..4]);
+                return;
+            }
+            _ => {}
+        }
+        self.apply_porter_duff(src, dst);
+    }
+
+    /// Composite the straight-alpha RGBA pixel `src` with `dst` like [`CompositeOp::apply`], with the source's contribution scaled by `opacity`.
+    ///
+    /// For Porter-Duff operators, this scales the source alpha. For blend modes, this blends from `dst` to the result.
+    pub fn apply_with_opacity(&self, src: &[u8], dst: &mut [u8], opacity: u8) {
+        match (self, opacity) {
+            (_, 255) => self.apply(src, dst),
+            (Self::SrcOver, _) => source_over(src, dst, opacity),
+            (Self::Add | Self::Multiply | Self::Screen, _) => {
+                let mut blended = [dst[0], dst[1], dst[2], dst[3]];
+                self.apply(src, &mut blended);
+                dst.iter_mut()
+                    .zip(blended)
+                    .for_each(|(d, b)| *d = lerp(*d, b, opacity));
+            }
+            _ => {
+                let src = [src[0], src[1], src[2], mul_div_255(src[3], opacity)];
+                self.apply_porter_duff(&src, dst);
+            }
+        }
+    }
+
+    fn apply_porter_duff(&self, src: &[u8], dst: &mut [u8]) {
+        let sa = src[3] as f32 / 255.;
+        let da = dst[3] as f32 / 255.;
+        let (fa, fb) = match self {
+            Self::Clear => (0., 0.),
+            Self::Src => (1., 0.),
+            Self::Dst => (0., 1.),
+            Self::SrcOver => (1., 1. - sa),
+            Self::DstOver => (1. - da, 1.),
+            Self::SrcIn => (da, 0.),
+            Self::DstIn => (0., sa),
+            Self::SrcOut => (1. - da, 0.),
+            Self::DstOut => (0., 1. - sa),
+            Self::SrcAtop => (da, 1. - sa),
+            Self::DstAtop => (1. - da, sa),
+            Self::Xor => (1. - da, 1. - sa),
+            Self::Plus => (1., 1.),
+            Self::Add | Self::Multiply | Self::Screen => unreachable!(),
+        };
+        let out_a = (sa * fa + da * fb).min(1.);
+        (0..3).for_each(|i| {
+            // Blend premultiplied colors, then convert back to straight alpha.
+            let c = src[i] as f32 * sa * fa + dst[i] as f32 * da * fb;
+            dst[i] = if out_a > 0. {
+                (c / out_a).round().min(255.) as u8
+            } else {
+                0
+            };
+        });
+        dst[3] = (out_a * 255.).round() as u8;
+    }
+}
+
+/// Blit the RGBA image `src` onto the RGBA image `dst`, combining each pixel using the operator `op`.
+///
+/// This is the same as [`blit_blend`] except for the operator. Alpha is straight (not premultiplied).
+/// See [`CompositeOp::apply_with_opacity`] for how `opacity` is applied.
+pub fn blit_composite(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    op: CompositeOp,
+    opacity: u8,
+) {
+    // Bytewise modes blend whole rows at once, which the compiler can vectorize.
+    let row_op: fn(&[u8], &mut [u8]) = match (op, opacity) {
+        (CompositeOp::Add, 255) => add,
+        (CompositeOp::Multiply, 255) => multiply,
+        (CompositeOp::Screen, 255) => screen,
+        _ => {
+            let mode = WithOpacity { op, opacity };
+            return blit_mode(src, src_size, dst, dst_position, dst_size, RGBA, &mode);
+        }
+    };
+    if src_size.w > 0 && src_size.h > 0 {
+        perf::record(Strategy::Blend);
+        blit_rows_with(
+            src,
+            src_size,
+            dst,
+            dst_position,
+            dst_size,
+            RGBA,
+            |_, src, dst| row_op(src, dst),
+        );
+    }
+}
+
+/// Blend each byte of `src` into `dst` with a saturating add.
+fn add(src: &[u8], dst: &mut [u8]) {
+    dst.iter_mut()
+        .zip(src)
+        .for_each(|(d, s)| *d = d.saturating_add(*s));
+}
+
+/// Blend each byte of `src` into `dst` by multiplying them.
+fn multiply(src: &[u8], dst: &mut [u8]) {
+    dst.iter_mut()
+        .zip(src)
+        .for_each(|(d, s)| *d = mul_div_255(*d, *s));
+}
+
+/// Blend each byte of `src` into `dst` by multiplying their inverses.
+fn screen(src: &[u8], dst: &mut [u8]) {
+    dst.iter_mut()
+        .zip(src)
+        .for_each(|(d, s)| *d = 255 - mul_div_255(255 - *d, 255 - *s));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_blend() {
+        let src = [255, 0, 0, 255, 255, 0, 0, 0, 255, 255, 255, 128];
+        let src_size = Size { w: 3, h: 1 };
+        let mut dst = [0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 0, 0];
+        blit_blend(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            255,
+        );
+        assert_eq!(dst, [255, 0, 0, 255, 0, 0, 255, 255, 255, 255, 255, 128]);
+
+        // Half opacity.
+        let mut dst = [0, 0, 255, 255];
+        let size = Size { w: 1, h: 1 };
+        blit_blend(
+            &src[..4],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            128,
+        );
+        assert_eq!(dst, [128, 0, 127, 255]);
+        let mut dst = [0, 0, 255, 255];
+        blit_composite(
+            &[100; 4],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            CompositeOp::Add,
+            128,
+        );
+        assert_eq!(dst, [50, 50, 255, 255]);
+    }
+
+    #[test]
+    fn test_composite_op() {
+        let red = [255, 0, 0, 255];
+        let half_blue = [0, 0, 255, 128];
+        let transparent = [0, 0, 0, 0];
+        let composite = |op: CompositeOp, src: [u8; 4], dst: [u8; 4]| {
+            let mut dst = dst;
+            op.apply(&src, &mut dst);
+            dst
+        };
+        assert_eq!(
+            composite(CompositeOp::DstOver, red, half_blue),
+            [127, 0, 128, 255]
+        );
+        assert_eq!(
+            composite(CompositeOp::SrcIn, red, half_blue),
+            [255, 0, 0, 128]
+        );
+        assert_eq!(composite(CompositeOp::SrcIn, red, transparent), transparent);
+        assert_eq!(
+            composite(CompositeOp::DstOut, half_blue, red),
+            [255, 0, 0, 127]
+        );
+        assert_eq!(composite(CompositeOp::Xor, red, red), transparent);
+        assert_eq!(
+            composite(CompositeOp::Plus, half_blue, half_blue),
+            [0, 0, 255, 255]
+        );
+        assert_eq!(composite(CompositeOp::Clear, red, red), transparent);
+        assert_eq!(
+            composite(CompositeOp::Add, [200, 10, 0, 255], half_blue),
+            [200, 10, 255, 255]
+        );
+
+        let src = [100; 8];
+        let mut dst = [0, 100, 200, 255, 50, 50, 50, 50];
+        let size = Size { w: 2, h: 1 };
+        blit_composite(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            CompositeOp::Add,
+            255,
+        );
+        assert_eq!(dst, [100, 200, 255, 255, 150, 150, 150, 150]);
+        let gray = [128, 128, 128, 255];
+        assert_eq!(
+            composite(CompositeOp::Multiply, gray, [255, 100, 0, 255]),
+            [128, 50, 0, 255]
+        );
+        assert_eq!(
+            composite(CompositeOp::Screen, gray, [255, 100, 0, 255]),
+            [255, 178, 128, 255]
+        );
+    }
+}
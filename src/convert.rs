@@ -0,0 +1,363 @@
+use crate::{
+    PositionU, Rect, Size, get_index,
+    gradient::luminance,
+    stride::{GRAYSCALE, RGB, RGBA, Stride},
+};
+
+/// The channel layout of an 8-bit-per-channel pixel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    Gray,
+    Rgb,
+    Rgba,
+    Bgr,
+    Bgra,
+}
+
+impl PixelFormat {
+    /// The per-pixel stride.
+    pub const fn stride(&self) -> Stride {
+        match self {
+            Self::Gray => GRAYSCALE,
+            Self::Rgb | Self::Bgr => RGB,
+            Self::Rgba | Self::Bgra => RGBA,
+        }
+    }
+
+    /// Read `pixel` as RGBA. Formats without alpha are opaque.
+    pub const fn read_rgba(&self, pixel: &[u8]) -> [u8; 4] {
+        match self {
+            Self::Gray => [pixel[0], pixel[0], pixel[0], 255],
+            Self::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+            Self::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            Self::Bgr => [pixel[2], pixel[1], pixel[0], 255],
+            Self::Bgra => [pixel[2], pixel[1], pixel[0], pixel[3]],
+        }
+    }
+
+    /// Write the RGBA color `rgba` to `pixel`. Gray is the Rec. 601 luma. Formats without alpha drop it.
+    pub fn write_rgba(&self, rgba: [u8; 4], pixel: &mut [u8]) {
+        let [r, g, b, a] = rgba;
+        match self {
+            Self::Gray => pixel[0] = luminance(r, g, b),
+            Self::Rgb => pixel[..3].copy_from_slice(&[r, g, b]),
+            Self::Rgba => pixel[..4].copy_from_slice(&rgba),
+            Self::Bgr => pixel[..3].copy_from_slice(&[b, g, r]),
+            Self::Bgra => pixel[..4].copy_from_slice(&[b, g, r, a]),
+        }
+    }
+}
+
+/// A pixel layout that [`blit_convert`] can convert from or to.
+pub trait Format: Copy {
+    /// The number of bytes per pixel.
+    fn stride(&self) -> Stride;
+}
+
+/// Converts pixels from the format `self` to the format `D`.
+///
+/// This is implemented for every pair of [`PixelFormat`]s, and for every pair of [`crate::PixelType`]s whose pixels implement [`From`].
+pub trait Convert<D: Format>: Format {
+    /// Convert the pixel `src` to `dst_format` and write it to `dst`.
+    fn convert(&self, dst_format: &D, src: &[u8], dst: &mut [u8]);
+
+    /// Blit all of `src` onto `dst` with a specialized fast path, if there is one.
+    /// Returns false if there isn't, in which case [`blit_convert`] converts each pixel with [`Convert::convert`].
+    #[allow(clippy::too_many_arguments)]
+    fn blit_fast(
+        &self,
+        _src: &[u8],
+        _src_size: &Size,
+        _dst: &mut [u8],
+        _dst_position: &PositionU,
+        _dst_size: &Size,
+        _dst_format: &D,
+    ) -> bool {
+        false
+    }
+}
+
+impl Format for PixelFormat {
+    fn stride(&self) -> Stride {
+        PixelFormat::stride(self)
+    }
+}
+
+impl Convert<PixelFormat> for PixelFormat {
+    #[inline]
+    fn convert(&self, dst_format: &PixelFormat, src: &[u8], dst: &mut [u8]) {
+        dst_format.write_rgba(self.read_rgba(src), dst);
+    }
+
+    fn blit_fast(
+        &self,
+        src: &[u8],
+        src_size: &Size,
+        dst: &mut [u8],
+        dst_position: &PositionU,
+        dst_size: &Size,
+        dst_format: &PixelFormat,
+    ) -> bool {
+        match (*self, *dst_format) {
+            (src_format, dst_format) if src_format == dst_format => {
+                // The rows are copied without conversion.
+                let stride = src_format.stride();
+                (0..src_size.h).for_each(|src_y| {
+                    let src_index = get_index(0, src_y, src_size.w, stride);
+                    let dst_index =
+                        get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+                    dst[dst_index..dst_index + src_size.w * stride]
+                        .copy_from_slice(&src[src_index..src_index + src_size.w * stride]);
+                });
+            }
+            (PixelFormat::Rgb, PixelFormat::Rgba) => {
+                blit_rgb_to_rgba(src, src_size, dst, dst_position, dst_size, 255)
+            }
+            (PixelFormat::Rgba, PixelFormat::Rgb) => {
+                blit_rgba_to_rgb(src, src_size, dst, dst_position, dst_size)
+            }
+            (PixelFormat::Rgba, PixelFormat::Bgra) | (PixelFormat::Bgra, PixelFormat::Rgba) => {
+                blit_swizzle(src, src_size, dst, dst_position, dst_size, SWAP_RB)
+            }
+            (PixelFormat::Gray, PixelFormat::Rgb) => {
+                blit_gray_to_color(src, src_size, dst, dst_position, dst_size, None)
+            }
+            (PixelFormat::Gray, PixelFormat::Rgba) => {
+                blit_gray_to_color(src, src_size, dst, dst_position, dst_size, Some(255))
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Blit `src` onto `dst`, converting each pixel from `src_format` to `dst_format`, e.g. to copy an RGB image onto a BGRA window surface.
+///
+/// This is the same as [`crate::blit`] except that the images can have different pixel formats.
+/// The formats are usually [`PixelFormat`]s, or [`crate::PixelType`]s for custom pixel types. See [`Convert`].
+///
+/// If two [`PixelFormat`]s are the same, the rows are copied without conversion.
+/// RGB to RGBA, RGBA to RGB, gray to RGB or RGBA, and RGBA to BGRA or back use the fast paths
+/// [`blit_rgb_to_rgba`], [`blit_rgba_to_rgb`], [`blit_gray_to_color`], and [`blit_swizzle`].
+#[allow(clippy::too_many_arguments)]
+pub fn blit_convert<S: Convert<D>, D: Format>(
+    src: &[u8],
+    src_size: &Size,
+    src_format: S,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    dst_format: D,
+) {
+    if src_format.blit_fast(src, src_size, dst, dst_position, dst_size, &dst_format) {
+        return;
+    }
+    let (src_stride, dst_stride) = (src_format.stride(), dst_format.stride());
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, src_stride);
+        let dst_index = get_index(
+            dst_position.x,
+            dst_position.y + src_y,
+            dst_size.w,
+            dst_stride,
+        );
+        src[src_index..src_index + src_size.w * src_stride]
+            .chunks_exact(src_stride.get())
+            .zip(
+                dst[dst_index..dst_index + src_size.w * dst_stride]
+                    .chunks_exact_mut(dst_stride.get()),
+            )
+            .for_each(|(s, d)| src_format.convert(&dst_format, s, d));
+    });
+}
+
+/// Blit the RGB image `src` onto the RGBA image `dst`, setting the alpha of every pixel to `alpha`.
+///
+/// This is the most common mismatch between decoded images and window surfaces, so it has its own fast path.
+pub fn blit_rgb_to_rgba(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    alpha: u8,
+) {
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, RGB);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, RGBA);
+        src[src_index..src_index + src_size.w * RGB]
+            .chunks_exact(RGB.get())
+            .zip(dst[dst_index..dst_index + src_size.w * RGBA].chunks_exact_mut(RGBA.get()))
+            .for_each(|(s, d)| {
+                d[..3].copy_from_slice(s);
+                d[3] = alpha;
+            });
+    });
+}
+
+/// Blit the RGBA image `src` onto the RGB image `dst`, dropping the alpha channel, e.g. to write to an RGB-only encoder or display.
+///
+/// Alpha is discarded, not blended. Use [`crate::blit_blend`] first to composite onto a background.
+pub fn blit_rgba_to_rgb(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+) {
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, RGBA);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, RGB);
+        src[src_index..src_index + src_size.w * RGBA]
+            .chunks_exact(RGBA.get())
+            .zip(dst[dst_index..dst_index + src_size.w * RGB].chunks_exact_mut(RGB.get()))
+            .for_each(|(s, d)| d.copy_from_slice(&s[..3]));
+    });
+}
+
+/// Blit the grayscale image `src` onto `dst`, copying each gray value into the red, green, and blue channels,
+/// e.g. to preview a font coverage map or a heightmap.
+///
+/// If `alpha` is `None`, `dst` is RGB. Otherwise, `dst` is RGBA and the alpha of every pixel is set to `alpha`.
+pub fn blit_gray_to_color(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    alpha: Option<u8>,
+) {
+    let dst_stride = if alpha.is_some() { RGBA } else { RGB };
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, GRAYSCALE);
+        let dst_index = get_index(
+            dst_position.x,
+            dst_position.y + src_y,
+            dst_size.w,
+            dst_stride,
+        );
+        let src_row = &src[src_index..src_index + src_size.w];
+        let dst_row = &mut dst[dst_index..dst_index + src_size.w * dst_stride];
+        match alpha {
+            Some(alpha) => dst_row
+                .chunks_exact_mut(RGBA.get())
+                .zip(src_row)
+                .for_each(|(d, s)| d.copy_from_slice(&[*s, *s, *s, alpha])),
+            None => dst_row
+                .chunks_exact_mut(RGB.get())
+                .zip(src_row)
+                .for_each(|(d, s)| d.fill(*s)),
+        }
+    });
+}
+
+/// The swizzle order that swaps the red and blue channels of 4-byte pixels, i.e. converts RGBA to BGRA and BGRA to RGBA.
+pub const SWAP_RB: [usize; 4] = [2, 1, 0, 3];
+
+/// Blit the 4-byte-per-pixel image `src` onto `dst`, reordering each pixel's channels, e.g. to copy RGBA pixels onto a BGRA swapchain.
+///
+/// Channel `i` of each destination pixel is channel `order[i]` of the source pixel. See [`SWAP_RB`].
+pub fn blit_swizzle(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    order: [usize; 4],
+) {
+    let src_w_stride = src_size.w * RGBA;
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, RGBA);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, RGBA);
+        src[src_index..src_index + src_w_stride]
+            .chunks_exact(RGBA.get())
+            .zip(dst[dst_index..dst_index + src_w_stride].chunks_exact_mut(RGBA.get()))
+            .for_each(|(s, d)| d.copy_from_slice(&order.map(|i| s[i])));
+    });
+}
+
+/// Reorder the channels of each 4-byte pixel within `rect` of `dst` in place. See [`blit_swizzle`].
+///
+/// `rect` is clipped to `dst_size`.
+pub fn swizzle_rect(dst: &mut [u8], dst_size: &Size, rect: &Rect, order: [usize; 4]) {
+    let x1 = rect.right().min(dst_size.w);
+    let y1 = rect.bottom().min(dst_size.h);
+    if x1 <= rect.position.x {
+        return;
+    }
+    (rect.position.y..y1).for_each(|y| {
+        let index = get_index(rect.position.x, y, dst_size.w, RGBA);
+        dst[index..index + (x1 - rect.position.x) * RGBA]
+            .chunks_exact_mut(RGBA.get())
+            .for_each(|pixel| {
+                let swizzled = order.map(|i| pixel[i]);
+                pixel.copy_from_slice(&swizzled);
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_convert() {
+        let src = [10, 20, 30, 255, 255, 255];
+        let size = Size { w: 2, h: 1 };
+        let mut dst = [0; 8];
+        blit_convert(
+            &src,
+            &size,
+            PixelFormat::Rgb,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            PixelFormat::Bgra,
+        );
+        assert_eq!(dst, [30, 20, 10, 255, 255, 255, 255, 255]);
+
+        let mut gray = [0; 2];
+        blit_convert(
+            &dst,
+            &size,
+            PixelFormat::Bgra,
+            &mut gray,
+            &PositionU::default(),
+            &size,
+            PixelFormat::Gray,
+        );
+        assert_eq!(gray, [18, 255]);
+
+        let mut dst = [0; 8];
+        blit_rgb_to_rgba(&src, &size, &mut dst, &PositionU::default(), &size, 128);
+        assert_eq!(dst, [10, 20, 30, 128, 255, 255, 255, 128]);
+        let mut rgb = [0; 6];
+        blit_rgba_to_rgb(&dst, &size, &mut rgb, &PositionU::default(), &size);
+        assert_eq!(rgb, src);
+        blit_gray_to_color(&[7, 9], &size, &mut rgb, &PositionU::default(), &size, None);
+        assert_eq!(rgb, [7, 7, 7, 9, 9, 9]);
+        blit_gray_to_color(
+            &[7, 9],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            Some(1),
+        );
+        assert_eq!(dst, [7, 7, 7, 1, 9, 9, 9, 1]);
+
+        let rgba = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut bgra = [0; 8];
+        blit_swizzle(
+            &rgba,
+            &size,
+            &mut bgra,
+            &PositionU::default(),
+            &size,
+            SWAP_RB,
+        );
+        assert_eq!(bgra, [3, 2, 1, 4, 7, 6, 5, 8]);
+        swizzle_rect(&mut bgra, &size, &Rect::new(1, 0, 1, 1), SWAP_RB);
+        assert_eq!(bgra, [3, 2, 1, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    PositionI, PositionU, Rect, Size,
+    blend::source_over,
+    blit, clip, get_index,
+    perf::{self, Strategy},
+    read_rows,
+    stride::{RGBA, Stride},
+};
+
+/// A software-rendered mouse cursor that saves and restores the pixels underneath it.
+///
+/// Each frame, call [`Cursor::restore`] before drawing to `dst` and [`Cursor::composite`] after,
+/// so the cursor never leaves a trail and is never drawn over.
+///
+/// If the stride is [`RGBA`], the cursor is alpha-blended. Otherwise, it's copied.
+pub struct Cursor {
+    image: Vec<u8>,
+    size: Size,
+    stride: Stride,
+    hotspot: PositionU,
+    position: PositionI,
+    visible: bool,
+    /// The area of `dst` under the cursor the last time it was composited, and its pixels.
+    save_under: Option<(Rect, Vec<u8>)>,
+}
+
+impl Cursor {
+    /// Create a visible cursor at (0, 0).
+    ///
+    /// - `image` is the cursor image, of size `size`.
+    /// - `hotspot` is the pixel of `image` that points at the cursor's position, e.g. the tip of an arrow.
+    pub fn new(image: Vec<u8>, size: Size, stride: Stride, hotspot: PositionU) -> Self {
+        Self {
+            image,
+            size,
+            stride,
+            hotspot,
+            position: PositionI::default(),
+            visible: true,
+            save_under: None,
+        }
+    }
+
+    /// Move the cursor's hotspot to `position`. The change is drawn on the next [`Cursor::composite`].
+    pub fn move_to(&mut self, position: PositionI) {
+        self.position = position;
+    }
+
+    /// The position of the cursor's hotspot.
+    pub const fn position(&self) -> PositionI {
+        self.position
+    }
+
+    /// Hide the cursor. It's erased on the next [`Cursor::restore`] or [`Cursor::composite`].
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Show the cursor.
+    pub fn show(&mut self) {
+        self.visible = true;
+    }
+
+    /// Returns true if the cursor is visible.
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Erase the cursor from `dst` by restoring the pixels that were under it. Does nothing if the cursor isn't drawn.
+    pub fn restore(&mut self, dst: &mut [u8], dst_size: &Size) {
+        if let Some((rect, pixels)) = self.save_under.take() {
+            blit(
+                &pixels,
+                &rect.size,
+                dst,
+                &rect.position,
+                dst_size,
+                self.stride,
+            );
+        }
+    }
+
+    /// Erase the cursor from its previous position in `dst`, then draw it at its current position if it's visible.
+    pub fn composite(&mut self, dst: &mut [u8], dst_size: &Size) {
+        self.restore(dst, dst_size);
+        if !self.visible {
+            return;
+        }
+        let position = PositionI {
+            x: self.position.x - self.hotspot.x.cast_signed(),
+            y: self.position.y - self.hotspot.y.cast_signed(),
+        };
+        let clip = clip(&position, dst_size, &self.size);
+        let rect = Rect {
+            position: clip.dst_position,
+            size: clip.size,
+        };
+        if rect.is_empty() {
+            return;
+        }
+        let pixels = read_rows(dst, dst_size, &rect, self.stride)
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+        if self.stride == RGBA {
+            perf::record(Strategy::Blend);
+        }
+        let (size, dst_position) = (clip.size, clip.dst_position);
+        let row_len = size.w * self.stride;
+        (0..size.h).for_each(|y| {
+            let src_index = get_index(
+                clip.src_offset.x,
+                clip.src_offset.y + y,
+                self.size.w,
+                self.stride,
+            );
+            let dst_index = get_index(dst_position.x, dst_position.y + y, dst_size.w, self.stride);
+            let src = &self.image[src_index..src_index + row_len];
+            let dst = &mut dst[dst_index..dst_index + row_len];
+            if self.stride == RGBA {
+                src.chunks_exact(4)
+                    .zip(dst.chunks_exact_mut(4))
+                    .for_each(|(src, dst)| source_over(src, dst, 255));
+            } else {
+                dst.copy_from_slice(src);
+            }
+        });
+        self.save_under = Some((rect, pixels));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_cursor() {
+        let dst_size = Size { w: 4, h: 2 };
+        let mut dst = [1, 2, 3, 4, 5, 6, 7, 8];
+        let background = dst;
+        let mut cursor = Cursor::new(
+            vec![9; 4],
+            Size { w: 2, h: 2 },
+            GRAYSCALE,
+            PositionU { x: 1, y: 1 },
+        );
+        cursor.move_to(PositionI { x: 1, y: 1 });
+        cursor.composite(&mut dst, &dst_size);
+        assert_eq!(dst, [9, 9, 3, 4, 9, 9, 7, 8]);
+
+        // Moving the cursor erases it from its previous position.
+        cursor.move_to(PositionI { x: 4, y: 0 });
+        cursor.composite(&mut dst, &dst_size);
+        assert_eq!(dst, [1, 2, 3, 9, 5, 6, 7, 8]);
+
+        cursor.hide();
+        cursor.composite(&mut dst, &dst_size);
+        assert_eq!(dst, background);
+    }
+}
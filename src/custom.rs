@@ -0,0 +1,102 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// Blit `src` onto `dst`, combining each source pixel with its destination pixel by calling `f(src_pixel, dst_pixel)`.
+///
+/// This is for blend math that the crate doesn't provide, e.g. difference or color dodge. Each slice is `stride` bytes long.
+/// If `f` doesn't need to be called per pixel, [`blit_rows_with`] has less overhead.
+pub fn blit_with<F>(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    mut f: F,
+) where
+    F: FnMut(&[u8], &mut [u8]),
+{
+    blit_rows_with(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        stride,
+        |_, src, dst| {
+            src.chunks_exact(stride.get())
+                .zip(dst.chunks_exact_mut(stride.get()))
+                .for_each(|(src, dst)| f(src, dst));
+        },
+    );
+}
+
+/// Blit `src` onto `dst`, combining each source row with the region of the destination row that it covers by calling `f(src_y, src_row, dst_row)`.
+///
+/// `src_y` is the row's index in `src`. Both rows are `src_size.w * stride` bytes long.
+pub fn blit_rows_with<F>(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    mut f: F,
+) where
+    F: FnMut(usize, &[u8], &mut [u8]),
+{
+    let src_w_stride = src_size.w * stride;
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, stride);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+        f(
+            src_y,
+            &src[src_index..src_index + src_w_stride],
+            &mut dst[dst_index..dst_index + src_w_stride],
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_blit_with() {
+        let size = Size { w: 2, h: 1 };
+        let src = [10, 20, 30, 200, 200, 200];
+        let mut dst = [20, 20, 20, 0, 100, 255];
+        // Difference.
+        blit_with(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            RGB,
+            |src, dst| {
+                dst.iter_mut()
+                    .zip(src)
+                    .for_each(|(d, s)| *d = d.abs_diff(*s))
+            },
+        );
+        assert_eq!(dst, [10, 0, 10, 200, 100, 55]);
+
+        let mut rows = Vec::new();
+        let mut dst = [0; 12];
+        blit_rows_with(
+            &src,
+            &Size { w: 1, h: 2 },
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &size,
+            RGB,
+            |y, src, dst| {
+                rows.push(y);
+                dst.copy_from_slice(src);
+            },
+        );
+        assert_eq!(rows, [0, 1]);
+        assert_eq!(dst, [0, 0, 0, 10, 20, 30, 0, 0, 0, 200, 200, 200]);
+    }
+}
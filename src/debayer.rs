@@ -0,0 +1,83 @@
+use crate::{Size, stride::RGB};
+
+/// The layout of the 2x2 color filter array of a RAW sensor image, read from the top-left pixel left to right, then top to bottom.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CfaPattern {
+    #[default]
+    Rggb,
+    Bggr,
+    Grbg,
+    Gbrg,
+}
+
+impl CfaPattern {
+    /// The channel (0 = red, 1 = green, 2 = blue) that the pixel at `(x, y)` samples.
+    pub const fn channel(&self, x: usize, y: usize) -> usize {
+        let cell = match self {
+            Self::Rggb => [0, 1, 1, 2],
+            Self::Bggr => [2, 1, 1, 0],
+            Self::Grbg => [1, 0, 2, 1],
+            Self::Gbrg => [1, 2, 0, 1],
+        };
+        cell[(x % 2) + (y % 2) * 2]
+    }
+}
+
+/// Demosaic the 8-bit RAW sensor image `src` into the RGB image `dst` using bilinear interpolation.
+///
+/// - `src` has one byte per pixel and `dst` has [`RGB`] bytes per pixel. Both are of size `size`.
+/// - `pattern` is the sensor's color filter array layout.
+///
+/// Each missing channel is the average of the neighboring pixels that sample it. Edge pixels only use the neighbors that exist.
+pub fn debayer(src: &[u8], size: &Size, pattern: CfaPattern, dst: &mut [u8]) {
+    let (w, h) = (size.w, size.h);
+    dst.chunks_exact_mut(RGB.get())
+        .take(w * h)
+        .enumerate()
+        .for_each(|(i, pixel)| {
+            let (x, y) = (i % w, i / w);
+            let own = pattern.channel(x, y);
+            let mut sums = [0u32; 3];
+            let mut counts = [0u32; 3];
+            (y.saturating_sub(1)..(y + 2).min(h)).for_each(|ny| {
+                (x.saturating_sub(1)..(x + 2).min(w)).for_each(|nx| {
+                    let channel = pattern.channel(nx, ny);
+                    if channel != own {
+                        sums[channel] += src[nx + ny * w] as u32;
+                        counts[channel] += 1;
+                    }
+                });
+            });
+            pixel.iter_mut().enumerate().for_each(|(channel, c)| {
+                *c = if channel == own {
+                    src[i]
+                } else {
+                    (sums[channel] + counts[channel] / 2)
+                        .checked_div(counts[channel])
+                        .unwrap_or(0) as u8
+                };
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debayer() {
+        // A uniformly colored RGGB image demosaics to that color, including the edges.
+        let (r, g, b) = (200, 100, 50);
+        let size = Size { w: 4, h: 4 };
+        let src = (0..16)
+            .map(|i| match CfaPattern::Rggb.channel(i % 4, i / 4) {
+                0 => r,
+                1 => g,
+                _ => b,
+            })
+            .collect::<Vec<u8>>();
+        let mut dst = vec![0; 16 * RGB.get()];
+        debayer(&src, &size, CfaPattern::Rggb, &mut dst);
+        assert!(dst.chunks_exact(3).all(|pixel| pixel == [r, g, b]));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    PositionU, Size,
+    color::{ENCODE_LEN, decode_lut},
+    get_index,
+    stride::Stride,
+};
+
+/// The sRGB primaries' RGB to XYZ matrix, adapted to the D50 white point used by ICC profiles.
+const SRGB_TO_XYZ_D50: [[f32; 3]; 3] = [
+    [0.436_074_7, 0.385_064_9, 0.143_080_4],
+    [0.222_504_5, 0.716_878_6, 0.060_616_9],
+    [0.013_932_2, 0.097_104_5, 0.714_173_3],
+];
+
+/// A simple color-managed display transform, applied to 8-bit sRGB pixels:
+///
+/// 1. Decode sRGB to linear light.
+/// 2. Multiply by a 3×3 matrix that converts sRGB primaries to the display's primaries.
+/// 3. Encode with the display's gamma.
+///
+/// Decoding and encoding use lookup tables, so this is cheap enough to apply during a present blit.
+#[derive(Clone)]
+pub struct DisplayTransform {
+    matrix: [[f32; 3]; 3],
+    decode: [f32; 256],
+    encode: Box<[u8; ENCODE_LEN]>,
+}
+
+impl DisplayTransform {
+    /// Create a transform from a row-major 3×3 `matrix` that converts linear sRGB to linear display RGB, and the display's `gamma`.
+    pub fn new(matrix: [[f32; 3]; 3], gamma: f32) -> Self {
+        let decode = *decode_lut();
+        let mut encode = Box::new([0; ENCODE_LEN]);
+        encode.iter_mut().enumerate().for_each(|(i, value)| {
+            let linear = i as f32 / (ENCODE_LEN - 1) as f32;
+            *value = (linear.powf(1. / gamma) * 255.).round() as u8;
+        });
+        Self {
+            matrix,
+            decode,
+            encode,
+        }
+    }
+
+    /// Create a transform from a minimal parse of an RGB display ICC profile.
+    ///
+    /// Only matrix/TRC profiles are supported: the `rXYZ`, `gXYZ`, and `bXYZ` tags must be present, and the `rTRC` tag must be a single gamma value.
+    /// If the `rTRC` tag is missing or isn't a single gamma value, a gamma of 2.2 is assumed.
+    ///
+    /// Returns `None` if the profile can't be parsed.
+    pub fn from_icc(profile: &[u8]) -> Option<Self> {
+        let r = read_xyz(profile, b"rXYZ")?;
+        let g = read_xyz(profile, b"gXYZ")?;
+        let b = read_xyz(profile, b"bXYZ")?;
+        // The columns of the display's RGB to XYZ matrix are the primaries.
+        let display_to_xyz = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+        let xyz_to_display = invert(&display_to_xyz)?;
+        let gamma = read_gamma(profile, b"rTRC").unwrap_or(2.2);
+        Some(Self::new(
+            multiply(&xyz_to_display, &SRGB_TO_XYZ_D50),
+            gamma,
+        ))
+    }
+
+    /// Apply the transform in-place to every pixel in `buffer`.
+    ///
+    /// `stride` must be at least 3. The first three channels are treated as RGB and any others, such as alpha, are left unchanged.
+    /// If `stride` is less than 3, `buffer` is unchanged.
+    pub fn apply(&self, buffer: &mut [u8], stride: Stride) {
+        if stride.get() < 3 {
+            return;
+        }
+        buffer
+            .chunks_exact_mut(stride.get())
+            .for_each(|pixel| self.transform_pixel(pixel));
+    }
+
+    fn transform_row(&self, src: &[u8], dst: &mut [u8], stride: Stride) {
+        dst.copy_from_slice(src);
+        self.apply(dst, stride);
+    }
+
+    #[inline]
+    fn transform_pixel(&self, pixel: &mut [u8]) {
+        let rgb = [
+            self.decode[pixel[0] as usize],
+            self.decode[pixel[1] as usize],
+            self.decode[pixel[2] as usize],
+        ];
+        self.matrix.iter().enumerate().for_each(|(i, row)| {
+            let linear = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+            let index = (linear.clamp(0., 1.) * (ENCODE_LEN - 1) as f32 + 0.5) as usize;
+            pixel[i] = self.encode[index];
+        });
+    }
+}
+
+/// Blit `src` onto `dst` like [`crate::blit`], applying `transform` to each pixel as it's copied.
+///
+/// `stride` must be at least 3. See [`DisplayTransform::apply`]. If it's less than 3, `dst` is unchanged.
+pub fn blit_display_transform(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    transform: &DisplayTransform,
+) {
+    if src_size.w > 0 && src_size.h > 0 && stride.get() >= 3 {
+        let src_w_stride = src_size.w * stride;
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, stride);
+            let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+            transform.transform_row(
+                &src[src_index..src_index + src_w_stride],
+                &mut dst[dst_index..dst_index + src_w_stride],
+                stride,
+            );
+        });
+    }
+}
+
+/// Returns the data of the ICC tag with the signature `signature`.
+fn find_tag<'a>(profile: &'a [u8], signature: &[u8; 4]) -> Option<&'a [u8]> {
+    // The tag count is untrusted, so don't look past the end of the tag table.
+    let count = (read_u32(profile, 128)? as usize).min(profile.len().saturating_sub(132) / 12);
+    (0..count).find_map(|i| {
+        let entry = 132 + i * 12;
+        if profile.get(entry..entry + 4)? == signature {
+            let offset = read_u32(profile, entry + 4)? as usize;
+            let size = read_u32(profile, entry + 8)? as usize;
+            profile.get(offset..offset.checked_add(size)?)
+        } else {
+            None
+        }
+    })
+}
+
+/// Read an `XYZType` tag.
+fn read_xyz(profile: &[u8], signature: &[u8; 4]) -> Option<[f32; 3]> {
+    let tag = find_tag(profile, signature)?;
+    if tag.get(0..4)? != b"XYZ " {
+        return None;
+    }
+    let s15_fixed_16 = |index: usize| Some(read_u32(tag, index)? as i32 as f32 / 65536.);
+    Some([s15_fixed_16(8)?, s15_fixed_16(12)?, s15_fixed_16(16)?])
+}
+
+/// Read a `curveType` tag that contains a single gamma value.
+fn read_gamma(profile: &[u8], signature: &[u8; 4]) -> Option<f32> {
+    let tag = find_tag(profile, signature)?;
+    if tag.get(0..4)? != b"curv" || read_u32(tag, 8)? != 1 {
+        return None;
+    }
+    let u8_fixed_8 = u16::from_be_bytes(tag.get(12..14)?.try_into().ok()?);
+    Some(u8_fixed_8 as f32 / 256.)
+}
+
+fn read_u32(bytes: &[u8], index: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(index..index + 4)?.try_into().ok()?,
+    ))
+}
+
+fn multiply(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn invert(m: &[[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let cofactor = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum::<f32>();
+    if determinant.abs() < f32::EPSILON {
+        None
+    } else {
+        // The inverse is the transposed matrix of cofactors divided by the determinant.
+        Some(std::array::from_fn(|i| {
+            std::array::from_fn(|j| cofactor(j, i) / determinant)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGB, RGBA};
+
+    const IDENTITY: [[f32; 3]; 3] = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+
+    #[test]
+    fn test_display_transform() {
+        let transform = DisplayTransform::new([[0., 1., 0.], [1., 0., 0.], [0., 0., 1.]], 2.2);
+        let mut buffer = [255, 0, 0, 7, 0, 0, 0, 255, 255, 255, 255, 9];
+        transform.apply(&mut buffer, RGBA);
+        assert_eq!(buffer, [0, 255, 0, 7, 0, 0, 0, 255, 255, 255, 255, 9]);
+
+        let src = [255u8; 2 * 2 * RGB.get()];
+        let mut dst = [0u8; 4 * 4 * RGB.get()];
+        let transform = DisplayTransform::new(IDENTITY, 2.2);
+        blit_display_transform(
+            &src,
+            &Size { w: 2, h: 2 },
+            &mut dst,
+            &PositionU { x: 1, y: 1 },
+            &Size { w: 4, h: 4 },
+            RGB,
+            &transform,
+        );
+        assert_eq!(dst.iter().filter(|c| **c == 255).count(), 2 * 2 * RGB);
+
+        // Grayscale pixels don't have three channels.
+        let mut buffer = [255, 0, 0];
+        transform.apply(&mut buffer, GRAYSCALE);
+        assert_eq!(buffer, [255, 0, 0]);
+        let mut dst = [0; 3];
+        blit_display_transform(
+            &[255; 3],
+            &Size { w: 3, h: 1 },
+            &mut dst,
+            &PositionU::default(),
+            &Size { w: 3, h: 1 },
+            GRAYSCALE,
+            &transform,
+        );
+        assert_eq!(dst, [0; 3]);
+    }
+
+    #[test]
+    fn test_from_icc() {
+        // Build a minimal profile whose primaries are sRGB's.
+        let mut profile = vec![0u8; 132];
+        profile[128..132].copy_from_slice(&4u32.to_be_bytes());
+        let tags_start = 132 + 4 * 12;
+        let mut tags = Vec::new();
+        for (i, signature) in [b"rXYZ", b"gXYZ", b"bXYZ"].into_iter().enumerate() {
+            let offset = tags_start + tags.len();
+            profile.extend_from_slice(signature);
+            profile.extend_from_slice(&(offset as u32).to_be_bytes());
+            profile.extend_from_slice(&20u32.to_be_bytes());
+            tags.extend_from_slice(b"XYZ \0\0\0\0");
+            (0..3).for_each(|row| {
+                let value = (SRGB_TO_XYZ_D50[row][i] * 65536.).round() as i32;
+                tags.extend_from_slice(&value.to_be_bytes());
+            });
+        }
+        let offset = tags_start + tags.len();
+        profile.extend_from_slice(b"rTRC");
+        profile.extend_from_slice(&(offset as u32).to_be_bytes());
+        profile.extend_from_slice(&14u32.to_be_bytes());
+        tags.extend_from_slice(b"curv\0\0\0\0");
+        tags.extend_from_slice(&1u32.to_be_bytes());
+        tags.extend_from_slice(&((2.2f32 * 256.) as u16).to_be_bytes());
+        profile.extend_from_slice(&tags);
+
+        let transform = DisplayTransform::from_icc(&profile).unwrap();
+        // The matrix should be (almost) the identity.
+        transform.matrix.iter().enumerate().for_each(|(i, row)| {
+            row.iter().enumerate().for_each(|(j, value)| {
+                assert!((value - IDENTITY[i][j]).abs() < 1e-3);
+            })
+        });
+        assert!(DisplayTransform::from_icc(&profile[..140]).is_none());
+
+        // A hostile tag count shouldn't scan past the end of the tag table.
+        let mut profile = vec![0u8; 132];
+        profile[128..132].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(find_tag(&profile, b"rXYZ").is_none());
+        assert!(DisplayTransform::from_icc(&profile).is_none());
+    }
+}
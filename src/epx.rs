@@ -0,0 +1,186 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// Blit `src` onto `dst` scaled up 2× with the Scale2x (EPX) algorithm, which smooths diagonal edges in pixel art without adding new colors.
+///
+/// `dst_position` is the top-left position of the scaled image, which is `src_size.w * 2` by `src_size.h * 2` pixels and must fit within `dst`.
+/// Pixels are compared byte for byte. Pixels past the edges of `src` are treated as copies of the nearest edge pixel.
+pub fn blit_scale2x(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    for_each_neighborhood(src, src_size, stride, |x, y, n| {
+        let [_, b, _, d, e, f, _, h, _] = n;
+        let block = if b != h && d != f {
+            [
+                if d == b { d } else { e },
+                if b == f { f } else { e },
+                if d == h { d } else { e },
+                if h == f { f } else { e },
+            ]
+        } else {
+            [e; 4]
+        };
+        write_block(&block, 2, x, y, dst, dst_position, dst_size, stride);
+    });
+}
+
+/// Blit `src` onto `dst` scaled up 3× with the Scale3x algorithm. See [`blit_scale2x`].
+///
+/// The scaled image is `src_size.w * 3` by `src_size.h * 3` pixels and must fit within `dst`.
+pub fn blit_scale3x(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    for_each_neighborhood(src, src_size, stride, |x, y, n| {
+        let [a, b, c, d, e, f, g, h, i] = n;
+        let block = if b != h && d != f {
+            [
+                if d == b { d } else { e },
+                if (d == b && e != c) || (b == f && e != a) {
+                    b
+                } else {
+                    e
+                },
+                if b == f { f } else { e },
+                if (d == b && e != g) || (d == h && e != a) {
+                    d
+                } else {
+                    e
+                },
+                e,
+                if (b == f && e != i) || (h == f && e != c) {
+                    f
+                } else {
+                    e
+                },
+                if d == h { d } else { e },
+                if (d == h && e != i) || (h == f && e != g) {
+                    h
+                } else {
+                    e
+                },
+                if h == f { f } else { e },
+            ]
+        } else {
+            [e; 9]
+        };
+        write_block(&block, 3, x, y, dst, dst_position, dst_size, stride);
+    });
+}
+
+/// Call `f(x, y, neighborhood)` for each pixel of `src`.
+/// The neighborhood is the 3×3 block of pixels centered on `(x, y)` in row-major order, with edge pixels repeated past the edges.
+fn for_each_neighborhood<F>(src: &[u8], src_size: &Size, stride: Stride, mut f: F)
+where
+    F: FnMut(usize, usize, [&[u8]; 9]),
+{
+    let pixel = |x: usize, y: usize| {
+        let index = get_index(x, y, src_size.w, stride);
+        &src[index..index + stride.get()]
+    };
+    (0..src_size.h).for_each(|y| {
+        let ys = [y.saturating_sub(1), y, (y + 1).min(src_size.h - 1)];
+        (0..src_size.w).for_each(|x| {
+            let xs = [x.saturating_sub(1), x, (x + 1).min(src_size.w - 1)];
+            f(x, y, std::array::from_fn(|i| pixel(xs[i % 3], ys[i / 3])));
+        });
+    });
+}
+
+/// Write the `factor` × `factor` `block` of pixels for the source pixel `(x, y)`.
+#[allow(clippy::too_many_arguments)]
+fn write_block(
+    block: &[&[u8]],
+    factor: usize,
+    x: usize,
+    y: usize,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    block.iter().enumerate().for_each(|(i, pixel)| {
+        let index = get_index(
+            dst_position.x + x * factor + i % factor,
+            dst_position.y + y * factor + i / factor,
+            dst_size.w,
+            stride,
+        );
+        dst[index..index + stride.get()].copy_from_slice(pixel);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_scale2x() {
+        // The corners of a checkerboard are rounded off.
+        let src = [1, 0, 0, 1];
+        let size = Size { w: 2, h: 2 };
+        let dst_size = Size { w: 4, h: 4 };
+        let mut dst = [9; 16];
+        blit_scale2x(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &dst_size,
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [1, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 1]);
+
+        // A flat color is unchanged.
+        let mut dst = [0; 36];
+        blit_scale3x(
+            &[5; 4],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &Size { w: 6, h: 6 },
+            GRAYSCALE,
+        );
+        assert!(dst.iter().all(|c| *c == 5));
+    }
+
+    #[test]
+    fn test_scale3x() {
+        // A diagonal line is thickened into a smooth band, which exercises the edge rules.
+        // 1 0 0
+        // 0 1 0
+        // 0 0 1
+        let src = [1, 0, 0, 0, 1, 0, 0, 0, 1];
+        let mut dst = [9; 81];
+        blit_scale3x(
+            &src,
+            &Size { w: 3, h: 3 },
+            &mut dst,
+            &PositionU::default(),
+            &Size { w: 9, h: 9 },
+            GRAYSCALE,
+        );
+        #[rustfmt::skip]
+        let expected = [
+            1, 1, 1, 0, 0, 0, 0, 0, 0,
+            1, 1, 0, 1, 0, 0, 0, 0, 0,
+            1, 0, 0, 1, 0, 0, 0, 0, 0,
+            0, 1, 1, 1, 1, 1, 0, 0, 0,
+            0, 0, 0, 1, 1, 1, 0, 0, 0,
+            0, 0, 0, 1, 1, 1, 1, 1, 0,
+            0, 0, 0, 0, 0, 1, 0, 0, 1,
+            0, 0, 0, 0, 0, 1, 0, 1, 1,
+            0, 0, 0, 0, 0, 0, 1, 1, 1,
+        ];
+        assert_eq!(dst, expected);
+    }
+}
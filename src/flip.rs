@@ -0,0 +1,83 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// Blit `src` onto `dst`, mirrored horizontally, e.g. to draw a left-facing sprite from a right-facing asset.
+///
+/// This is the same as [`crate::blit`] except that each row is reversed as it's copied.
+pub fn blit_flip_h(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    let src_w_stride = src_size.w * stride;
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, stride);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+        dst[dst_index..dst_index + src_w_stride]
+            .chunks_exact_mut(stride.get())
+            .zip(
+                src[src_index..src_index + src_w_stride]
+                    .chunks_exact(stride.get())
+                    .rev(),
+            )
+            .for_each(|(d, s)| d.copy_from_slice(s));
+    });
+}
+
+/// Blit `src` onto `dst`, mirrored vertically, e.g. to import an image from a bottom-up format such as BMP.
+///
+/// This is the same as [`crate::blit`] except that the rows are copied in reverse order.
+pub fn blit_flip_v(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    let src_w_stride = src_size.w * stride;
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_size.h - 1 - src_y, src_size.w, stride);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+        dst[dst_index..dst_index + src_w_stride]
+            .copy_from_slice(&src[src_index..src_index + src_w_stride]);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_flip() {
+        let src = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let mut dst = [0; 18];
+        blit_flip_h(
+            &src,
+            &Size { w: 2, h: 2 },
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &Size { w: 3, h: 2 },
+            RGB,
+        );
+        assert_eq!(
+            dst,
+            [0, 0, 0, 4, 5, 6, 1, 2, 3, 0, 0, 0, 10, 11, 12, 7, 8, 9]
+        );
+        blit_flip_v(
+            &src,
+            &Size { w: 2, h: 2 },
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &Size { w: 3, h: 2 },
+            RGB,
+        );
+        assert_eq!(
+            dst,
+            [0, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0, 0, 1, 2, 3, 4, 5, 6]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// Blit the packed 1bpp bitmap `src` onto `dst`, drawing `color` where bits are set and leaving `dst` unchanged where they're clear,
+/// e.g. for bitmap-font glyphs and cursor masks.
+///
+/// - `src` has the same bit layout as [`crate::blit_rop_1bpp`]: eight pixels per byte, most significant bit first, and each row starts on a new byte.
+/// - `color` is a single pixel. Its length is the per-pixel stride of `dst`.
+pub fn blit_glyph(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    color: &[u8],
+) {
+    let Some(stride) = Stride::new(color.len()) else {
+        return;
+    };
+    let row_len = src_size.w.div_ceil(8);
+    (0..src_size.h).for_each(|src_y| {
+        let row = &src[src_y * row_len..(src_y + 1) * row_len];
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+        let dst_row = &mut dst[dst_index..dst_index + src_size.w * stride];
+        row.iter().enumerate().for_each(|(i, byte)| {
+            // Skip empty bytes, which are common in glyphs.
+            if *byte == 0 {
+                return;
+            }
+            (0..(src_size.w - i * 8).min(8)).for_each(|bit| {
+                if byte & (0x80 >> bit) != 0 {
+                    let x = i * 8 + bit;
+                    dst_row[x * stride..(x + 1) * stride].copy_from_slice(color);
+                }
+            });
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_glyph() {
+        // A 3x2 glyph.
+        let src = [0b1010_0000, 0b0100_0000];
+        let mut dst = [0; 4 * 2 * 3];
+        blit_glyph(
+            &src,
+            &Size { w: 3, h: 2 },
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &Size { w: 4, h: 2 },
+            &[255, 128, 0],
+        );
+        assert_eq!(
+            dst,
+            [
+                0, 0, 0, 255, 128, 0, 0, 0, 0, 255, 128, 0, //
+                0, 0, 0, 0, 0, 0, 255, 128, 0, 0, 0, 0
+            ]
+        );
+    }
+}
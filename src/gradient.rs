@@ -0,0 +1,104 @@
+use crate::{Rect, Size, get_index, stride::Stride};
+
+/// An RGB color at a position along a gradient, where 0 is the start and 1 is the end.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorStop {
+    pub position: f32,
+    pub color: [u8; 3],
+}
+
+/// Recolor `rect` in `dst` by replacing each pixel's luminance with the color at that point along `gradient`, like a Photoshop gradient map.
+///
+/// - `dst` is a flat byte slice of an image of size `dst_size`.
+/// - `stride` must be at least 3. The first three channels are treated as RGB and any others, such as alpha, are left unchanged.
+/// - `gradient` doesn't need to be sorted. Before the first stop and after the last stop, the color is constant.
+///
+/// `rect` is clipped to `dst_size`. If `gradient` is empty or `stride` is less than 3, this does nothing.
+pub fn apply_gradient_map(
+    dst: &mut [u8],
+    dst_size: &Size,
+    rect: &Rect,
+    stride: Stride,
+    gradient: &[ColorStop],
+) {
+    let x1 = rect.right().min(dst_size.w);
+    let y1 = rect.bottom().min(dst_size.h);
+    if gradient.is_empty() || stride.get() < 3 || rect.position.x >= x1 || rect.position.y >= y1 {
+        return;
+    }
+    let lut = gradient_lut(gradient);
+    (rect.position.y..y1).for_each(|y| {
+        let index = get_index(rect.position.x, y, dst_size.w, stride);
+        dst[index..index + (x1 - rect.position.x) * stride]
+            .chunks_exact_mut(stride.get())
+            .for_each(|pixel| {
+                let color = &lut[luminance(pixel[0], pixel[1], pixel[2]) as usize];
+                pixel[..3].copy_from_slice(color);
+            });
+    });
+}
+
+/// Returns the Rec. 601 luma of an RGB color.
+pub(crate) const fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    ((77 * r as u32 + 150 * g as u32 + 29 * b as u32 + 128) >> 8) as u8
+}
+
+/// Sample `gradient` at 256 evenly spaced positions. `gradient` must not be empty.
+pub(crate) fn gradient_lut(gradient: &[ColorStop]) -> [[u8; 3]; 256] {
+    let mut stops = gradient.to_vec();
+    stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+    std::array::from_fn(|i| {
+        let t = i as f32 / 255.;
+        match stops.iter().position(|stop| stop.position >= t) {
+            Some(0) => stops[0].color,
+            Some(index) => {
+                let (a, b) = (&stops[index - 1], &stops[index]);
+                let range = b.position - a.position;
+                let f = if range > 0. {
+                    (t - a.position) / range
+                } else {
+                    1.
+                };
+                std::array::from_fn(|c| {
+                    (a.color[c] as f32 + (b.color[c] as f32 - a.color[c] as f32) * f).round() as u8
+                })
+            }
+            None => stops[stops.len() - 1].color,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGBA};
+
+    #[test]
+    fn test_apply_gradient_map() {
+        let gradient = [
+            ColorStop {
+                position: 1.,
+                color: [255, 255, 0],
+            },
+            ColorStop {
+                position: 0.,
+                color: [0, 0, 255],
+            },
+        ];
+        let dst_size = Size { w: 3, h: 1 };
+        let mut dst = [0, 0, 0, 10, 255, 255, 255, 20, 128, 128, 128, 30];
+        apply_gradient_map(&mut dst, &dst_size, &Rect::new(0, 0, 3, 1), RGBA, &gradient);
+        assert_eq!(dst, [0, 0, 255, 10, 255, 255, 0, 20, 128, 128, 127, 30]);
+
+        // Grayscale pixels don't have three channels.
+        let mut dst = [0, 255, 128];
+        apply_gradient_map(
+            &mut dst,
+            &dst_size,
+            &Rect::new(0, 0, 3, 1),
+            GRAYSCALE,
+            &gradient,
+        );
+        assert_eq!(dst, [0, 255, 128]);
+    }
+}
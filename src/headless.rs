@@ -0,0 +1,166 @@
+//! Render frames offscreen and write them to PNG files, without a display server.
+//!
+//! This is meant for CI-friendly visual tests of code built on top of `blittle`.
+
+use std::{
+    fmt,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use png::{BitDepth, ColorType, Encoder, EncodingError};
+
+use crate::{
+    Size,
+    stride::{GRAYSCALE, RGB, RGBA, Stride},
+};
+
+/// An error returned by the headless render functions.
+#[derive(Debug)]
+pub enum HeadlessError {
+    /// The stride doesn't correspond to a PNG color type. Only [`GRAYSCALE`], [`RGB`], and [`RGBA`] are supported.
+    UnsupportedStride(Stride),
+    /// There was an IO or encoding error while writing a PNG.
+    Encoding(EncodingError),
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedStride(stride) => write!(f, "Unsupported stride: {stride}"),
+            Self::Encoding(error) => write!(f, "PNG encoding error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {}
+
+impl From<EncodingError> for HeadlessError {
+    fn from(value: EncodingError) -> Self {
+        Self::Encoding(value)
+    }
+}
+
+/// Call `render` once per frame on an offscreen buffer and write each frame to `directory` as `frame_00000.png`, `frame_00001.png`, etc.
+///
+/// - `size` and `stride` describe the offscreen buffer. It starts zeroed and is *not* cleared between frames.
+/// - `render` receives the buffer and the frame index.
+///
+/// Returns the paths of the written files.
+pub fn render_to_pngs<F>(
+    size: &Size,
+    stride: Stride,
+    frames: usize,
+    directory: &Path,
+    mut render: F,
+) -> Result<Vec<PathBuf>, HeadlessError>
+where
+    F: FnMut(&mut [u8], usize),
+{
+    let color_type = get_color_type(stride)?;
+    let mut buffer = vec![0; size.w * size.h * stride];
+    (0..frames)
+        .map(|frame| {
+            render(&mut buffer, frame);
+            let path = directory.join(format!("frame_{frame:05}.png"));
+            let mut encoder = get_encoder(&path, size, color_type)?;
+            encoder.set_compression(png::Compression::Fast);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&buffer)?;
+            writer.finish()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Call `render` once per frame on an offscreen buffer and write every frame to a single animated PNG at `path`.
+///
+/// - `size` and `stride` describe the offscreen buffer. It starts zeroed and is *not* cleared between frames.
+/// - `frame_delay_ms` is how long each frame is displayed.
+/// - `render` receives the buffer and the frame index.
+pub fn render_to_apng<F>(
+    size: &Size,
+    stride: Stride,
+    frames: usize,
+    frame_delay_ms: u16,
+    path: &Path,
+    mut render: F,
+) -> Result<(), HeadlessError>
+where
+    F: FnMut(&mut [u8], usize),
+{
+    let color_type = get_color_type(stride)?;
+    let mut encoder = get_encoder(path, size, color_type)?;
+    encoder.set_animated(frames as u32, 0)?;
+    encoder.set_frame_delay(frame_delay_ms, 1000)?;
+    let mut writer = encoder.write_header()?;
+    let mut buffer = vec![0; size.w * size.h * stride];
+    for frame in 0..frames {
+        render(&mut buffer, frame);
+        writer.write_image_data(&buffer)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+const fn get_color_type(stride: Stride) -> Result<ColorType, HeadlessError> {
+    if stride.get() == GRAYSCALE.get() {
+        Ok(ColorType::Grayscale)
+    } else if stride.get() == RGB.get() {
+        Ok(ColorType::Rgb)
+    } else if stride.get() == RGBA.get() {
+        Ok(ColorType::Rgba)
+    } else {
+        Err(HeadlessError::UnsupportedStride(stride))
+    }
+}
+
+fn get_encoder(
+    path: &Path,
+    size: &Size,
+    color_type: ColorType,
+) -> Result<Encoder<'static, BufWriter<File>>, HeadlessError> {
+    let file = File::create(path).map_err(EncodingError::IoError)?;
+    let mut encoder = Encoder::new(BufWriter::new(file), size.w as u32, size.h as u32);
+    encoder.set_color(color_type);
+    encoder.set_depth(BitDepth::Eight);
+    Ok(encoder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PositionU, blit};
+
+    #[test]
+    fn test_headless() {
+        let directory = std::env::temp_dir().join("blittle_headless");
+        std::fs::create_dir_all(&directory).unwrap();
+        let size = Size { w: 64, h: 64 };
+        let src = [255u8; 8 * 8 * RGB.get()];
+        let src_size = Size { w: 8, h: 8 };
+        let render = |dst: &mut [u8], frame: usize| {
+            let position = PositionU {
+                x: frame * 8,
+                y: frame * 8,
+            };
+            blit(&src, &src_size, dst, &position, &size, RGB);
+        };
+        let paths = render_to_pngs(&size, RGB, 4, &directory, render).unwrap();
+        assert_eq!(paths.len(), 4);
+        assert!(paths.iter().all(|path| path.exists()));
+        render_to_apng(&size, RGB, 4, 100, &directory.join("anim.png"), render).unwrap();
+        assert!(matches!(
+            render_to_apng(
+                &size,
+                Stride::new(7).unwrap(),
+                4,
+                100,
+                &directory.join("anim.png"),
+                render
+            ),
+            Err(HeadlessError::UnsupportedStride(_))
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{Rect, Size};
+
+/// A summed-area table of a grayscale image, for constant-time sums and means over any rect,
+/// e.g. for large box blurs, adaptive thresholding, or measuring average brightness.
+pub struct IntegralImage {
+    /// `(w + 1) * (h + 1)` sums, where entry `(x, y)` is the sum of every pixel above and to the left of `(x, y)`.
+    sums: Vec<u64>,
+    size: Size,
+}
+
+impl IntegralImage {
+    /// Build the table from `src`, a grayscale image (one byte per pixel) of size `size`.
+    pub fn build(src: &[u8], size: &Size) -> Self {
+        let table_w = size.w + 1;
+        let mut sums = vec![0u64; table_w * (size.h + 1)];
+        (0..size.h).for_each(|y| {
+            let mut row_sum = 0;
+            (0..size.w).for_each(|x| {
+                row_sum += src[x + y * size.w] as u64;
+                sums[(x + 1) + (y + 1) * table_w] = sums[(x + 1) + y * table_w] + row_sum;
+            });
+        });
+        Self { sums, size: *size }
+    }
+
+    /// The size of the source image.
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The sum of the pixels in `rect`, which is clipped to the image.
+    pub fn sum(&self, rect: &Rect) -> u64 {
+        let x0 = rect.position.x.min(self.size.w);
+        let y0 = rect.position.y.min(self.size.h);
+        let x1 = rect.right().min(self.size.w);
+        let y1 = rect.bottom().min(self.size.h);
+        let w = self.size.w + 1;
+        self.sums[x1 + y1 * w] + self.sums[x0 + y0 * w]
+            - self.sums[x0 + y1 * w]
+            - self.sums[x1 + y0 * w]
+    }
+
+    /// The mean of the pixels in `rect`, which is clipped to the image. Returns 0 if the clipped rect is empty.
+    pub fn mean(&self, rect: &Rect) -> f32 {
+        let w = rect
+            .right()
+            .min(self.size.w)
+            .saturating_sub(rect.position.x);
+        let h = rect
+            .bottom()
+            .min(self.size.h)
+            .saturating_sub(rect.position.y);
+        let count = w * h;
+        if count == 0 {
+            0.
+        } else {
+            self.sum(rect) as f32 / count as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integral_image() {
+        let src = [1, 2, 3, 4, 5, 6];
+        let integral = IntegralImage::build(&src, &Size { w: 3, h: 2 });
+        assert_eq!(integral.sum(&Rect::new(0, 0, 3, 2)), 21);
+        assert_eq!(integral.sum(&Rect::new(1, 0, 2, 2)), 16);
+        assert_eq!(integral.mean(&Rect::new(2, 1, 5, 5)), 6.);
+        assert_eq!(integral.mean(&Rect::new(3, 0, 1, 1)), 0.);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+
+use crate::{Rect, Size, blit, read_rows, stride::Stride};
+
+/// The saved pixels of a region of an image.
+struct Entry {
+    rect: Rect,
+    stride: Stride,
+    pixels: Vec<u8>,
+}
+
+impl Entry {
+    /// Save the pixels of `rect` in `src`. `rect` is clipped to `src_size`.
+    fn new(src: &[u8], src_size: &Size, rect: &Rect, stride: Stride) -> Self {
+        let pixels = read_rows(src, src_size, rect, stride)
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+        let w = rect.right().min(src_size.w).saturating_sub(rect.position.x);
+        let h = rect
+            .bottom()
+            .min(src_size.h)
+            .saturating_sub(rect.position.y);
+        Self {
+            rect: Rect {
+                position: rect.position,
+                size: if w == 0 || h == 0 {
+                    Size::default()
+                } else {
+                    Size { w, h }
+                },
+            },
+            stride,
+            pixels,
+        }
+    }
+
+    /// Swap the saved pixels with the current pixels in `dst`.
+    /// Returns an entry with the pixels that were in `dst`.
+    fn swap(self, dst: &mut [u8], dst_size: &Size) -> Self {
+        let current = Self::new(dst, dst_size, &self.rect, self.stride);
+        blit(
+            &self.pixels,
+            &self.rect.size,
+            dst,
+            &self.rect.position,
+            dst_size,
+            self.stride,
+        );
+        current
+    }
+}
+
+/// An undo/redo history of regions of an image, for paint tools and level editors.
+///
+/// Call [`RegionJournal::record`] with the region that's about to change *before* each destructive blit.
+/// Memory is bounded: when the saved pixels exceed the journal's capacity, the oldest undo steps are discarded.
+///
+/// The journal doesn't own the image. Every call must use the same image and size.
+pub struct RegionJournal {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+    capacity: usize,
+    len: usize,
+}
+
+impl RegionJournal {
+    /// Create an empty journal that holds up to `capacity` bytes of undo history.
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// Save the pixels of `rect` in `dst` so that the next change to them can be undone. This clears the redo history.
+    ///
+    /// `rect` is clipped to `dst_size`. A region larger than the journal's capacity isn't saved, and clears the undo history.
+    pub fn record(&mut self, dst: &[u8], dst_size: &Size, rect: &Rect, stride: Stride) {
+        self.redo.clear();
+        let entry = Entry::new(dst, dst_size, rect, stride);
+        self.len += entry.pixels.len();
+        self.undo.push_back(entry);
+        while self.len > self.capacity {
+            match self.undo.pop_front() {
+                Some(entry) => self.len -= entry.pixels.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// Restore the most recently recorded region in `dst`. Returns false if there is nothing to undo.
+    pub fn undo(&mut self, dst: &mut [u8], dst_size: &Size) -> bool {
+        match self.undo.pop_back() {
+            Some(entry) => {
+                self.len -= entry.pixels.len();
+                self.redo.push(entry.swap(dst, dst_size));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply the most recently undone change in `dst`. Returns false if there is nothing to redo.
+    pub fn redo(&mut self, dst: &mut [u8], dst_size: &Size) -> bool {
+        match self.redo.pop() {
+            Some(entry) => {
+                let entry = entry.swap(dst, dst_size);
+                self.len += entry.pixels.len();
+                self.undo.push_back(entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The number of steps that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// The number of steps that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Discard all undo and redo history.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PositionU, stride::GRAYSCALE};
+
+    #[test]
+    fn test_region_journal() {
+        let dst_size = Size { w: 4, h: 4 };
+        let mut dst = vec![0u8; 4 * 4];
+        let mut journal = RegionJournal::new(8);
+        let src = [9u8; 4];
+        let src_size = Size { w: 2, h: 2 };
+        let paint = |dst: &mut [u8], journal: &mut RegionJournal, x: usize| {
+            journal.record(dst, &dst_size, &Rect::new(x, 0, 2, 2), GRAYSCALE);
+            blit(
+                &src,
+                &src_size,
+                dst,
+                &PositionU { x, y: 0 },
+                &dst_size,
+                GRAYSCALE,
+            );
+        };
+        paint(&mut dst, &mut journal, 0);
+        paint(&mut dst, &mut journal, 1);
+        paint(&mut dst, &mut journal, 2);
+        // Only two 4-byte steps fit.
+        assert_eq!(journal.undo_len(), 2);
+        assert_eq!(dst[..4], [9, 9, 9, 9]);
+
+        assert!(journal.undo(&mut dst, &dst_size));
+        assert_eq!(dst[..4], [9, 9, 9, 0]);
+        assert!(journal.undo(&mut dst, &dst_size));
+        assert_eq!(dst[..4], [9, 9, 0, 0]);
+        assert!(!journal.undo(&mut dst, &dst_size));
+
+        assert!(journal.redo(&mut dst, &dst_size));
+        assert_eq!(dst[..4], [9, 9, 9, 0]);
+        assert_eq!(journal.redo_len(), 1);
+        paint(&mut dst, &mut journal, 0);
+        assert_eq!(journal.redo_len(), 0);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// Blit `src` onto `dst`, skipping source pixels that match the color `key`, e.g. to key out a sprite's background.
+///
+/// - `key` is a single pixel. Its length is the per-pixel stride.
+/// - `tolerance` is the maximum per-channel difference from `key` that still counts as a match. Its length is the per-pixel stride.
+///   Use all zeros for an exact color key, or larger values for noisy backgrounds such as scanned or compressed assets.
+///
+/// If `tolerance` isn't the same length as `key`, `dst` is unchanged.
+/// Runs of non-matching pixels are copied at once, so sprites with large opaque areas are nearly as fast as [`crate::blit`].
+pub fn blit_keyed(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    key: &[u8],
+    tolerance: &[u8],
+) {
+    let Some(stride) = Stride::new(key.len()) else {
+        return;
+    };
+    if tolerance.len() != key.len() {
+        return;
+    }
+    let is_key = |pixel: &[u8]| {
+        pixel
+            .iter()
+            .zip(key)
+            .zip(tolerance)
+            .all(|((c, k), t)| c.abs_diff(*k) <= *t)
+    };
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, stride);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+        let src_row = &src[src_index..src_index + src_size.w * stride];
+        let dst_row = &mut dst[dst_index..dst_index + src_size.w * stride];
+        let mut x = 0;
+        while x < src_size.w {
+            // Find the next run of pixels that aren't keyed out.
+            let start = x;
+            while x < src_size.w && !is_key(&src_row[x * stride..(x + 1) * stride]) {
+                x += 1;
+            }
+            if x > start {
+                dst_row[start * stride..x * stride]
+                    .copy_from_slice(&src_row[start * stride..x * stride]);
+            }
+            x += 1;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_keyed() {
+        let key = [0, 255, 0];
+        let src = [1, 2, 3, 4, 250, 5, 0, 255, 0, 6, 7, 8];
+        let src_size = Size { w: 4, h: 1 };
+        let mut dst = [9; 12];
+        blit_keyed(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            &key,
+            &[0; 3],
+        );
+        assert_eq!(dst, [1, 2, 3, 4, 250, 5, 9, 9, 9, 6, 7, 8]);
+
+        let mut dst = [9; 12];
+        blit_keyed(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            &key,
+            &[8; 3],
+        );
+        assert_eq!(dst, [1, 2, 3, 9, 9, 9, 9, 9, 9, 6, 7, 8]);
+
+        // The tolerance must be the same length as the key.
+        let mut dst = [9; 12];
+        blit_keyed(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            &key,
+            &[8; 1],
+        );
+        assert_eq!(dst, [9; 12]);
+    }
+}
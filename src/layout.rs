@@ -0,0 +1,132 @@
+use crate::{PositionU, Size, stride::Stride};
+
+/// The memory layout of an image whose rows may be padded, e.g. a DirectX, V4L2, or wgpu staging buffer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    /// The width in pixels.
+    pub w: usize,
+    /// The height in pixels.
+    pub h: usize,
+    /// The distance in bytes from the start of one row to the start of the next. This is at least `w * stride`.
+    pub pitch: usize,
+    /// If true, the rows are stored bottom-up, i.e. the first row in memory is the bottom row of the image, e.g. a Windows DIB.
+    pub bottom_up: bool,
+}
+
+impl Layout {
+    /// The layout of an image of size `size` whose rows aren't padded, i.e. the layout that [`crate::blit`] assumes.
+    pub const fn packed(size: &Size, stride: Stride) -> Self {
+        Self {
+            w: size.w,
+            h: size.h,
+            pitch: size.w * stride.get(),
+            bottom_up: false,
+        }
+    }
+
+    /// The width and height.
+    pub const fn size(&self) -> Size {
+        Size {
+            w: self.w,
+            h: self.h,
+        }
+    }
+
+    /// The index of the first byte of row `y`, where row 0 is the top row.
+    pub const fn row_index(&self, y: usize) -> usize {
+        if self.bottom_up {
+            (self.h - 1 - y) * self.pitch
+        } else {
+            y * self.pitch
+        }
+    }
+
+    /// The index of the first byte of pixel `(x, y)`.
+    pub const fn index(&self, x: usize, y: usize, stride: Stride) -> usize {
+        self.row_index(y) + x * stride.get()
+    }
+
+    /// The minimum length in bytes of a buffer with this layout. The last row doesn't need to be padded.
+    pub const fn min_len(&self, stride: Stride) -> usize {
+        if self.w == 0 || self.h == 0 {
+            0
+        } else {
+            (self.h - 1) * self.pitch + self.w * stride.get()
+        }
+    }
+}
+
+/// Blit `src` onto `dst`, where either or both images may have padded rows.
+///
+/// This is the same as [`crate::blit`] except that the images' layouts include their row pitches,
+/// so the caller doesn't have to compute the offset of each row.
+/// Bottom-up images are read and written in the correct order without an intermediate flipped copy.
+pub fn blit_pitched(
+    src: &[u8],
+    src_layout: &Layout,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_layout: &Layout,
+    stride: Stride,
+) {
+    let row_len = src_layout.w * stride;
+    if row_len == 0 {
+        return;
+    }
+    (0..src_layout.h).for_each(|src_y| {
+        let src_index = src_layout.row_index(src_y);
+        let dst_index = dst_layout.index(dst_position.x, dst_position.y + src_y, stride);
+        dst[dst_index..dst_index + row_len].copy_from_slice(&src[src_index..src_index + row_len]);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_pitched() {
+        // 2x2 with 1 byte of padding per row, onto 3x2 with 2 bytes of padding per row.
+        let src = [1, 2, 0, 3, 4];
+        let src_layout = Layout {
+            w: 2,
+            h: 2,
+            pitch: 3,
+            bottom_up: false,
+        };
+        assert_eq!(src_layout.min_len(GRAYSCALE), 5);
+        let dst_layout = Layout {
+            w: 3,
+            h: 2,
+            pitch: 5,
+            bottom_up: false,
+        };
+        let mut dst = [9; 10];
+        blit_pitched(
+            &src,
+            &src_layout,
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &dst_layout,
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [9, 1, 2, 9, 9, 9, 3, 4, 9, 9]);
+
+        // Bottom-up to top-down is a vertical flip.
+        let mut dst = [9; 4];
+        blit_pitched(
+            &src,
+            &Layout {
+                bottom_up: true,
+                ..src_layout
+            },
+            &mut dst,
+            &PositionU::default(),
+            &Layout::packed(&Size { w: 2, h: 2 }, GRAYSCALE),
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [3, 4, 1, 2]);
+        assert_eq!(Layout::packed(&Size { w: 3, h: 2 }, GRAYSCALE).pitch, 3);
+    }
+}
@@ -1,16 +1,133 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod affine;
+mod aligned;
+mod anchor;
+mod blend;
+mod checked;
+mod checker;
+mod chunked;
+pub mod color;
+mod colormap;
+mod components;
+mod composite;
+mod convert;
+mod cursor;
+mod custom;
+#[cfg(feature = "debayer")]
+mod debayer;
+mod display;
+#[cfg(feature = "epx")]
+mod epx;
+mod flip;
+mod glyph;
+mod gradient;
+#[cfg(feature = "headless")]
+pub mod headless;
+mod integral;
+mod journal;
+mod key;
+mod layout;
+mod linear;
+mod lock;
+mod mask;
+mod mode;
 #[cfg(feature = "rayon")]
 mod multi_threaded;
+mod nine_slice;
+pub mod noise;
+mod packed;
+mod pattern;
+pub mod perf;
+mod pixel;
+mod planar;
 mod position;
+pub mod prelude;
+mod premultiplied;
+mod present;
+mod pyramid;
+mod rect;
+mod resolution;
+mod ring;
+mod rop;
+mod rotate;
+mod rows;
+mod scale;
+mod scroll;
+mod sheet;
 mod size;
+mod span;
+mod stamp;
+mod stipple;
 pub mod stride;
+pub mod time;
+mod thumbnail;
+mod tiled;
+mod tint;
+mod tonemap;
+mod transpose;
+pub use affine::*;
+pub use aligned::*;
+pub use anchor::*;
+pub use checked::*;
+pub use checker::*;
+pub use chunked::*;
+pub use colormap::*;
+pub use components::*;
+pub use composite::*;
+pub use convert::*;
+pub use cursor::*;
+pub use custom::*;
+#[cfg(feature = "debayer")]
+pub use debayer::*;
+pub use display::*;
+#[cfg(feature = "epx")]
+pub use epx::*;
+pub use flip::*;
+pub use glyph::*;
+pub use gradient::*;
+pub use integral::*;
+pub use journal::*;
+pub use key::*;
+pub use layout::*;
+pub use linear::*;
+pub use lock::*;
+pub use mask::*;
+pub use mode::*;
 #[cfg(feature = "rayon")]
 pub use multi_threaded::*;
+pub use nine_slice::*;
+pub use packed::*;
 
+pub use pattern::*;
+pub use pixel::*;
+pub use planar::*;
 pub use position::*;
-pub use size::Size;
+pub use premultiplied::*;
+pub use present::*;
+pub use pyramid::*;
+pub use rect::*;
+pub use resolution::*;
+pub use ring::*;
+pub use rop::*;
+pub use rotate::*;
+pub use rows::*;
+pub use scale::*;
+pub use scroll::*;
+pub use sheet::*;
+pub use size::*;
+pub use span::*;
+pub use stamp::*;
+pub use stipple::*;
+pub use thumbnail::*;
+pub use tiled::*;
+pub use tint::*;
+pub use tonemap::*;
+pub use transpose::*;
+
+use perf::Strategy;
+use stride::Stride;
 
 /// Blit `src` onto `dst`.
 ///
@@ -24,57 +141,156 @@ pub fn blit(
     dst: &mut [u8],
     dst_position: &PositionU,
     dst_size: &Size,
-    stride: usize,
+    stride: Stride,
+) {
+    blit_mode(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        stride,
+        &Overwrite,
+    );
+}
+
+/// Blit the region `src_rect` of `src` onto `dst`, e.g. one frame of a sprite sheet.
+///
+/// This is the same as [`blit`] except that only `src_rect` is copied. `src_rect` is clipped to `src_size`,
+/// and the copied region is clipped on both axes to the part of `dst_size` that's right of and below `dst_position`.
+pub fn blit_region(
+    src: &[u8],
+    src_size: &Size,
+    src_rect: &Rect,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
 ) {
-    if src_size.w > 0 && src_size.h > 0 {
-        let src_w_stride = src_size.w * stride;
-        (0..src_size.h).for_each(|src_y| {
-            let src_index = get_index(0, src_y, src_size.w, stride);
-            let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
-            dst[dst_index..dst_index + src_w_stride]
-                .copy_from_slice(&src[src_index..src_index + src_w_stride]);
-        });
+    let w = src_rect
+        .right()
+        .min(src_size.w)
+        .saturating_sub(src_rect.position.x)
+        .min(dst_size.w.saturating_sub(dst_position.x));
+    if w > 0 {
+        perf::record(Strategy::Rows);
+        blit_from_rows(
+            read_rows(src, src_size, src_rect, stride),
+            w,
+            dst,
+            dst_position,
+            dst_size,
+            stride,
+        );
     }
 }
 
-/// Clip `src_size` such that it fits within the rectangle defined by `dst_position` and `dst_size`.
-/// Returns `dst_position` as a clipped `PositionU` that can be used in [`blit`].
-pub fn clip(dst_position: &PositionI, dst_size: &Size, src_size: &mut Size) -> PositionU {
+/// The result of [`clip`]: where, and which part of, a source image can be blitted.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClipResult {
+    /// The top-left position in the destination image.
+    pub dst_position: PositionU,
+    /// The top-left position of the visible region in the source image.
+    /// This is non-zero if the source image hangs off of the top or left of the destination image.
+    pub src_offset: PositionU,
+    /// The size of the visible region. This is zero if nothing is visible.
+    pub size: Size,
+}
+
+impl ClipResult {
+    /// The visible region of the source image, e.g. for [`blit_region`].
+    pub const fn src_rect(&self) -> Rect {
+        Rect {
+            position: self.src_offset,
+            size: self.size,
+        }
+    }
+}
+
+/// Clip `src_size`, placed at `dst_position`, such that it fits within `dst_size`.
+/// Returns the clipped position, source offset, and size. Pass the result to [`blit_clip_result`].
+pub fn clip(dst_position: &PositionI, dst_size: &Size, src_size: &Size) -> ClipResult {
     // Check if the source image is totally out of bounds.
     if dst_position.x + (src_size.w.cast_signed()) < 0 || dst_position.y + (src_size.h.cast_signed()) < 0 {
-        src_size.w = 0;
-        src_size.h = 0;
-        PositionU::default()
+        ClipResult::default()
     } else {
+        let mut size = *src_size;
+        let mut src_offset = PositionU::default();
         let mut x = 0;
         if dst_position.x < 0 {
-            src_size.w = src_size.w.saturating_sub(dst_position.x.unsigned_abs());
+            src_offset.x = dst_position.x.unsigned_abs();
+            size.w = size.w.saturating_sub(src_offset.x);
         } else {
             x = dst_position.x.unsigned_abs();
         }
         let mut y = 0;
         if dst_position.y < 0 {
-            src_size.h = src_size.h.saturating_sub(dst_position.y.unsigned_abs());
+            src_offset.y = dst_position.y.unsigned_abs();
+            size.h = size.h.saturating_sub(src_offset.y);
         } else {
             y = dst_position.y.unsigned_abs();
         }
         let dst_position = PositionU { x, y };
         // This allows us to do unchecked subtraction.
         // The `blit` methods will also check `is_inside`.
-        if dst_position.x < dst_size.w && dst_position.y < dst_size.h {
-            src_size.w = src_size.w.min(dst_size.w - dst_position.x);
-            src_size.h = src_size.h.min(dst_size.h - dst_position.y);
-            dst_position
+        if dst_position.x < dst_size.w
+            && dst_position.y < dst_size.h
+            && size.w > 0
+            && size.h > 0
+        {
+            size.w = size.w.min(dst_size.w - dst_position.x);
+            size.h = size.h.min(dst_size.h - dst_position.y);
+            ClipResult {
+                dst_position,
+                src_offset,
+                size,
+            }
         } else {
-            *src_size = Size::default();
-            PositionU::default()
+            ClipResult::default()
         }
     }
 }
 
+/// Blit the visible region of `src` described by `clip`, the result of [`clip`], onto `dst`.
+///
+/// Unlike passing the clipped size to [`blit`], this reads from the correct part of `src` when it was placed at a negative position.
+pub fn blit_clip_result(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_size: &Size,
+    clip: &ClipResult,
+    stride: Stride,
+) {
+    blit_region(
+        src,
+        src_size,
+        &clip.src_rect(),
+        dst,
+        &clip.dst_position,
+        dst_size,
+        stride,
+    );
+}
+
+/// Clip `src` to `dst` and blit it, for sources that may be partially or totally off of `dst`.
+///
+/// This is the same as calling [`clip`] and then [`blit_clip_result`].
+pub fn blit_clipped(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionI,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    let clip = clip(dst_position, dst_size, src_size);
+    blit_clip_result(src, src_size, dst, dst_size, &clip, stride);
+}
+
 /// Converts a position, width, and stride to an index in a 1D byte slice.
-pub const fn get_index(x: usize, y: usize, w: usize, stride: usize) -> usize {
-    (x + y * w) * stride
+pub const fn get_index(x: usize, y: usize, w: usize, stride: Stride) -> usize {
+    (x + y * w) * stride.get()
 }
 
 #[cfg(test)]
@@ -90,8 +306,8 @@ mod tests {
 
     #[test]
     fn test_blit() {
-        let src = [255u8; SRC_W * SRC_H * RGB];
-        let mut dst = [0u8; DST_W * DST_H * RGB];
+        let src = [255u8; SRC_W * SRC_H * RGB.get()];
+        let mut dst = [0u8; DST_W * DST_H * RGB.get()];
 
         let dst_position = PositionU { x: 2, y: 12 };
         let dst_size = Size { w: DST_W, h: DST_H };
@@ -102,22 +318,72 @@ mod tests {
         save_png("blit.png", &dst, DST_W as u32, DST_H as u32);
     }
 
+    #[test]
+    fn test_blit_region() {
+        // A 2x2 sheet of 2x1 grayscale frames.
+        let src = [1, 1, 2, 2, 3, 3, 4, 4];
+        let src_size = Size { w: 4, h: 2 };
+        let mut dst = [0u8; 3 * 2];
+        let dst_size = Size { w: 3, h: 2 };
+        let src_rect = Rect::new(2, 1, 2, 1);
+        let dst_position = PositionU { x: 1, y: 1 };
+        blit_region(
+            &src,
+            &src_size,
+            &src_rect,
+            &mut dst,
+            &dst_position,
+            &dst_size,
+            stride::GRAYSCALE,
+        );
+        assert_eq!(dst, [0, 0, 0, 0, 4, 4]);
+
+        // A region that hangs off the right and bottom of `dst` is clipped on both axes.
+        let mut dst = [0u8; 3 * 2];
+        blit_region(
+            &src,
+            &src_size,
+            &Rect::new(0, 0, 4, 2),
+            &mut dst,
+            &PositionU { x: 1, y: 1 },
+            &dst_size,
+            stride::GRAYSCALE,
+        );
+        assert_eq!(dst, [0, 0, 0, 0, 1, 1]);
+    }
+
     #[test]
     fn test_clip() {
-        blit_clipped("clip_positive.png", 42, 16);
-        blit_clipped("clip_negative.png", -8, -8);
+        save_clipped("clip_positive.png", 42, 16);
+        save_clipped("clip_negative.png", -8, -8);
+
+        // The source offset skips the pixels that are off of the top-left of `dst`.
+        let src = [1, 2, 3, 4];
+        let src_size = Size { w: 2, h: 2 };
+        let mut dst = [0; 4];
+        let dst_size = Size { w: 2, h: 2 };
+        let clip = clip(&PositionI { x: -1, y: 1 }, &dst_size, &src_size);
+        assert_eq!(clip.src_offset, PositionU { x: 1, y: 0 });
+        assert_eq!(clip.size, Size { w: 1, h: 1 });
+        blit_clip_result(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            &clip,
+            stride::GRAYSCALE,
+        );
+        assert_eq!(dst, [0, 0, 2, 0]);
     }
 
-    fn blit_clipped(name: &str, x: isize, y: isize) {
-        let src = [255u8; SRC_W * SRC_H * RGB];
-        let mut dst = [0u8; DST_W * DST_H * RGB];
+    fn save_clipped(name: &str, x: isize, y: isize) {
+        let src = [255u8; SRC_W * SRC_H * RGB.get()];
+        let mut dst = [0u8; DST_W * DST_H * RGB.get()];
 
         let dst_position = PositionI { x, y };
         let dst_size = Size { w: DST_W, h: DST_H };
-        let mut src_size = Size { w: SRC_W, h: SRC_H };
-        let dst_position = clip(&dst_position, &dst_size, &mut src_size);
-
-        blit(&src, &src_size, &mut dst, &dst_position, &dst_size, RGB);
+        let src_size = Size { w: SRC_W, h: SRC_H };
+        blit_clipped(&src, &src_size, &mut dst, &dst_position, &dst_size, RGB);
         save_png(name, &dst, DST_W as u32, DST_H as u32);
     }
 
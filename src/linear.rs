@@ -0,0 +1,81 @@
+use crate::{
+    BlendMode, PositionU, Size,
+    blend::mul_div_255,
+    blit_mode,
+    color::{ENCODE_LEN, decode_lut, encode_index, encode_lut},
+    stride::RGBA,
+};
+
+/// Blit the sRGB RGBA image `src` onto the sRGB RGBA image `dst` using source-over alpha blending in linear light.
+///
+/// This is the same as [`crate::blit_blend`] except that colors are decoded to linear light before they're mixed and encoded back to sRGB afterwards.
+/// Blending sRGB values directly makes semi-transparent edges, e.g. antialiased text, look darker than they should.
+/// Decoding and encoding use the lookup tables in [`crate::color`]. Alpha is straight and isn't gamma-encoded.
+///
+/// `opacity` scales the alpha of every source pixel. 255 is fully opaque.
+pub fn blit_blend_linear(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    opacity: u8,
+) {
+    let mode = LinearOver {
+        opacity,
+        decode: decode_lut(),
+        encode: encode_lut(),
+    };
+    blit_mode(src, src_size, dst, dst_position, dst_size, RGBA, &mode);
+}
+
+/// Source-over blending in linear light, with the alpha of every source pixel scaled by `opacity`.
+struct LinearOver {
+    opacity: u8,
+    decode: &'static [f32; 256],
+    encode: &'static [u8; ENCODE_LEN],
+}
+
+impl BlendMode for LinearOver {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        match mul_div_255(src[3], self.opacity) {
+            0 => {}
+            255 => dst.copy_from_slice(src),
+            sa => {
+                let sa = sa as f32 / 255.;
+                let da = dst[3] as f32 / 255.;
+                let dst_weight = da * (1. - sa);
+                let out_a = sa + dst_weight;
+                (0..3).for_each(|i| {
+                    let c = (self.decode[src[i] as usize] * sa
+                        + self.decode[dst[i] as usize] * dst_weight)
+                        / out_a;
+                    dst[i] = self.encode[encode_index(c)];
+                });
+                dst[3] = (out_a * 255. + 0.5) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_blend_linear() {
+        // Half-transparent white over opaque black is brighter than in sRGB.
+        let size = Size { w: 2, h: 1 };
+        let mut dst = [0, 0, 0, 255, 0, 0, 0, 255];
+        blit_blend_linear(
+            &[255, 255, 255, 128, 255, 0, 0, 0],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            255,
+        );
+        assert_eq!(dst, [188, 188, 188, 255, 0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    marker::PhantomData,
+    slice::from_raw_parts_mut,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{Rect, Size, get_index, stride::Stride};
+
+/// Shares an image between threads so that each thread can paint a disjoint region of it.
+///
+/// [`RegionLock::lock_rect`] hands out a [`RegionGuard`] only if its rect doesn't overlap any other active guard,
+/// so concurrent painting doesn't need `unsafe` code or a lock around the whole image.
+pub struct RegionLock<'a> {
+    ptr: *mut u8,
+    size: Size,
+    stride: Stride,
+    active: Mutex<Vec<Rect>>,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+// Guards only ever access disjoint regions of the buffer.
+unsafe impl Send for RegionLock<'_> {}
+unsafe impl Sync for RegionLock<'_> {}
+
+impl<'a> RegionLock<'a> {
+    /// Share `dst`, an image of size `dst_size`.
+    ///
+    /// Panics if `dst` is shorter than `dst_size` requires.
+    pub fn new(dst: &'a mut [u8], dst_size: Size, stride: Stride) -> Self {
+        assert!(
+            dst.len() >= dst_size.w * dst_size.h * stride,
+            "`dst` is too short for `dst_size`"
+        );
+        Self {
+            ptr: dst.as_mut_ptr(),
+            size: dst_size,
+            stride,
+            active: Mutex::new(Vec::new()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Lock `rect` for painting. `rect` is clipped to the image.
+    ///
+    /// Returns `None` if `rect` overlaps a region that is already locked, or if it's empty after clipping.
+    pub fn lock_rect(&self, rect: &Rect) -> Option<RegionGuard<'_, 'a>> {
+        let x1 = rect.right().min(self.size.w);
+        let y1 = rect.bottom().min(self.size.h);
+        if rect.position.x >= x1 || rect.position.y >= y1 {
+            return None;
+        }
+        let rect = Rect {
+            position: rect.position,
+            size: Size {
+                w: x1 - rect.position.x,
+                h: y1 - rect.position.y,
+            },
+        };
+        let mut active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+        if active.iter().any(|other| overlaps(&rect, other)) {
+            return None;
+        }
+        active.push(rect);
+        Some(RegionGuard { lock: self, rect })
+    }
+}
+
+/// Returns true if `a` and `b` share at least one pixel.
+const fn overlaps(a: &Rect, b: &Rect) -> bool {
+    a.position.x < b.right()
+        && b.position.x < a.right()
+        && a.position.y < b.bottom()
+        && b.position.y < a.bottom()
+}
+
+/// Exclusive access to a region of a [`RegionLock`]'s image. The region is unlocked when the guard is dropped.
+pub struct RegionGuard<'l, 'a> {
+    lock: &'l RegionLock<'a>,
+    rect: Rect,
+}
+
+impl RegionGuard<'_, '_> {
+    /// The locked region, in the image's coordinates.
+    pub const fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The pixels of row `y` of the region, where `y` is relative to the top of the region.
+    ///
+    /// Panics if `y` is outside of the region.
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        assert!(y < self.rect.size.h, "`y` is outside of the locked region");
+        let index = get_index(
+            self.rect.position.x,
+            self.rect.position.y + y,
+            self.lock.size.w,
+            self.lock.stride,
+        );
+        // The row is within the buffer, and no other guard can access it while this one exists.
+        unsafe {
+            from_raw_parts_mut(
+                self.lock.ptr.add(index),
+                self.rect.size.w * self.lock.stride,
+            )
+        }
+    }
+
+    /// Blit `src`, an image of size `src_size`, at the top-left corner of the region, clipped to the region.
+    pub fn blit(&mut self, src: &[u8], src_size: &Size) {
+        let w = src_size.w.min(self.rect.size.w) * self.lock.stride;
+        (0..src_size.h.min(self.rect.size.h)).for_each(|y| {
+            let index = get_index(0, y, src_size.w, self.lock.stride);
+            self.row_mut(y)[..w].copy_from_slice(&src[index..index + w]);
+        });
+    }
+}
+
+impl Drop for RegionGuard<'_, '_> {
+    fn drop(&mut self) {
+        let mut active = self
+            .lock
+            .active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(i) = active.iter().position(|rect| *rect == self.rect) {
+            active.swap_remove(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_region_lock() {
+        let mut dst = [0u8; 4 * 2];
+        {
+            let lock = RegionLock::new(&mut dst, Size { w: 4, h: 2 }, GRAYSCALE);
+            let left = lock.lock_rect(&Rect::new(0, 0, 2, 2)).unwrap();
+            assert!(lock.lock_rect(&Rect::new(1, 1, 2, 2)).is_none());
+            let right = lock.lock_rect(&Rect::new(2, 0, 2, 2)).unwrap();
+            std::thread::scope(|s| {
+                for (mut guard, value) in [(left, 1), (right, 2)] {
+                    s.spawn(move || guard.blit(&[value; 4], &Size { w: 2, h: 2 }));
+                }
+            });
+            // Both guards were dropped, so the whole image can be locked again.
+            assert!(lock.lock_rect(&Rect::new(0, 0, 4, 2)).is_some());
+        }
+        assert_eq!(dst, [1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    PositionU, Size,
+    blend::lerp,
+    blit_rows_with,
+    perf::{self, Strategy},
+    stride::Stride,
+};
+
+/// How [`blit_masked`] uses each mask value.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MaskMode {
+    /// Copy the source pixel if the mask value isn't zero. Otherwise, leave the destination pixel unchanged.
+    #[default]
+    Gate,
+    /// Blend from the destination pixel to the source pixel by the mask value, where 255 is a copy. This allows soft edges.
+    Modulate,
+}
+
+/// Blit `src` onto `dst` through `mask`, e.g. for stencils, soft-edged sprites, and wipes, without baking alpha into `src`.
+///
+/// - `mask` is a grayscale image (one byte per pixel) of size `src_size`.
+/// - `mode` determines whether the mask gates or modulates each pixel. See [`MaskMode`].
+///
+/// Every channel is masked, including alpha if there is one.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_masked(
+    src: &[u8],
+    mask: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    mode: MaskMode,
+) {
+    if src_size.w == 0 || src_size.h == 0 {
+        return;
+    }
+    perf::record(Strategy::Blend);
+    blit_rows_with(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        stride,
+        |src_y, src, dst| {
+            src.chunks_exact(stride.get())
+                .zip(dst.chunks_exact_mut(stride.get()))
+                .zip(&mask[src_y * src_size.w..(src_y + 1) * src_size.w])
+                .for_each(|((src, dst), m)| match (mode, *m) {
+                    (_, 0) => {}
+                    (MaskMode::Gate, _) | (MaskMode::Modulate, 255) => dst.copy_from_slice(src),
+                    (MaskMode::Modulate, m) => dst
+                        .iter_mut()
+                        .zip(src)
+                        .for_each(|(d, s)| *d = lerp(*d, *s, m)),
+                });
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_masked() {
+        let src = [200, 200, 200];
+        let mask = [0, 128, 255];
+        let size = Size { w: 3, h: 1 };
+        let position = PositionU::default();
+        let mut dst = [0; 3];
+        blit_masked(
+            &src,
+            &mask,
+            &size,
+            &mut dst,
+            &position,
+            &size,
+            GRAYSCALE,
+            MaskMode::Gate,
+        );
+        assert_eq!(dst, [0, 200, 200]);
+        let mut dst = [0; 3];
+        blit_masked(
+            &src,
+            &mask,
+            &size,
+            &mut dst,
+            &position,
+            &size,
+            GRAYSCALE,
+            MaskMode::Modulate,
+        );
+        assert_eq!(dst, [0, 100, 200]);
+    }
+}
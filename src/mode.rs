@@ -0,0 +1,129 @@
+use crate::{
+    CompositeOp, PositionU, Rop, Size, get_index,
+    perf::{self, Strategy},
+    stride::Stride,
+};
+
+/// How a source pixel is combined with a destination pixel by [`blit_mode`].
+///
+/// [`blit_mode`] is generic over the mode, so each mode gets its own copy of the blit loop and `blend` can be inlined into it.
+/// A user-defined mode is as fast as a built-in one.
+pub trait BlendMode {
+    /// If true, `blend` copies `src` and ignores `dst`, so whole rows can be copied at once.
+    const OVERWRITE: bool = false;
+
+    /// Combine the pixel `src` with the pixel `dst`. Both are `stride` bytes long.
+    fn blend(&self, src: &[u8], dst: &mut [u8]);
+}
+
+/// Copy the source pixel, i.e. the same as [`crate::blit`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overwrite;
+
+impl BlendMode for Overwrite {
+    const OVERWRITE: bool = true;
+
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        dst.copy_from_slice(src);
+    }
+}
+
+/// Composite RGBA pixels with [`CompositeOp::apply`].
+impl BlendMode for CompositeOp {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        self.apply(src, dst);
+    }
+}
+
+/// Combine each byte with [`Rop::apply`].
+impl BlendMode for Rop {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        dst.iter_mut()
+            .zip(src)
+            .for_each(|(d, s)| *d = self.apply(*s, *d));
+    }
+}
+
+/// Blit `src` onto `dst`, combining each pixel with `mode`.
+///
+/// This is the same as [`crate::blit`] except for the blend mode. `blit` is `blit_mode` with [`Overwrite`].
+pub fn blit_mode<B: BlendMode>(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    mode: &B,
+) {
+    if src_size.w > 0 && src_size.h > 0 {
+        let src_w_stride = src_size.w * stride;
+        perf::record(if B::OVERWRITE {
+            Strategy::Rows
+        } else {
+            Strategy::Blend
+        });
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, stride);
+            let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+            let src_row = &src[src_index..src_index + src_w_stride];
+            let dst_row = &mut dst[dst_index..dst_index + src_w_stride];
+            if B::OVERWRITE {
+                dst_row.copy_from_slice(src_row);
+            } else {
+                src_row
+                    .chunks_exact(stride.get())
+                    .zip(dst_row.chunks_exact_mut(stride.get()))
+                    .for_each(|(src, dst)| mode.blend(src, dst));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    /// The absolute difference of each byte.
+    struct Difference;
+
+    impl BlendMode for Difference {
+        fn blend(&self, src: &[u8], dst: &mut [u8]) {
+            dst.iter_mut()
+                .zip(src)
+                .for_each(|(d, s)| *d = d.abs_diff(*s));
+        }
+    }
+
+    #[test]
+    fn test_blit_mode() {
+        let src = [10, 200];
+        let src_size = Size { w: 2, h: 1 };
+        let dst_size = Size { w: 3, h: 1 };
+        let mut dst = [50, 50, 50];
+        blit_mode(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &dst_size,
+            GRAYSCALE,
+            &Difference,
+        );
+        assert_eq!(dst, [50, 40, 150]);
+        blit_mode(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &dst_size,
+            GRAYSCALE,
+            &Overwrite,
+        );
+        assert_eq!(dst, [10, 200, 150]);
+    }
+}
@@ -1,8 +1,19 @@
 use std::slice::{from_raw_parts, from_raw_parts_mut};
 
-use crate::{PositionU, Size, get_index};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use crate::{
+    BlitError, PositionU, Size,
+    blend::{alpha_to_u8, blend_bytes},
+    checked::validate,
+    get_index,
+    perf::{self, Strategy},
+    present::assert_frame_lens,
+    stride::Stride,
+};
 pub use rayon::max_num_threads;
+use rayon::{
+    iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    slice::{ParallelSlice, ParallelSliceMut},
+};
 
 /// Blit using multiple threads by dividing `src` and `dst` into chunks and blitting each in parallel.
 ///
@@ -17,10 +28,11 @@ pub fn blit_multi_threaded(
     dst: &mut [u8],
     dst_position: &PositionU,
     dst_size: &Size,
-    stride: usize,
+    stride: Stride,
     num_threads: usize,
 ) {
     if src_size.w > 0 && src_size.h > 0 {
+        perf::record(Strategy::Threaded);
         let src_ptr = src.as_ptr();
         let dst_ptr = dst.as_mut_ptr();
         let src_w_stride = src_size.w * stride;
@@ -41,7 +53,7 @@ pub fn blit_multi_threaded(
             .collect::<Vec<(&[u8], &mut [u8])>>();
 
         // Iterate through chunks of slices.
-        let chunk_size = src.len() / num_threads;
+        let chunk_size = (src.len() / num_threads.max(1)).max(1);
         slices
             .into_par_iter()
             .chunks(chunk_size)
@@ -52,3 +64,74 @@ pub fn blit_multi_threaded(
             });
     }
 }
+
+/// Blit using multiple threads like [`blit_multi_threaded`], but return an error instead of panicking if the sizes are inconsistent
+/// or `num_threads` is zero.
+pub fn try_blit_multi_threaded(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    num_threads: usize,
+) -> Result<(), BlitError> {
+    if num_threads == 0 {
+        return Err(BlitError::ZeroThreads);
+    }
+    validate(src, src_size, dst, dst_position, dst_size, stride)?;
+    blit_multi_threaded(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        stride,
+        num_threads,
+    );
+    Ok(())
+}
+
+/// Blend two whole frames into `dst` using multiple threads. See [`crate::blend_frames`].
+///
+/// The frames are divided into `num_threads` chunks that are blended in parallel.
+/// Panics if the three slices aren't the same length.
+pub fn blend_frames_multi_threaded(
+    prev: &[u8],
+    curr: &[u8],
+    alpha: f32,
+    dst: &mut [u8],
+    num_threads: usize,
+) {
+    assert_frame_lens(prev, curr, dst);
+    perf::record(Strategy::Threaded);
+    let t = alpha_to_u8(alpha);
+    let chunk_size = dst.len().div_ceil(num_threads.max(1)).max(1);
+    dst.par_chunks_mut(chunk_size)
+        .zip(prev.par_chunks(chunk_size).zip(curr.par_chunks(chunk_size)))
+        .for_each(|(dst, (prev, curr))| blend_bytes(prev, curr, t, dst));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_try_blit_multi_threaded() {
+        let src = [1u8; 2 * 2 * RGB.get()];
+        let size = Size { w: 2, h: 2 };
+        let mut dst = [0u8; 2 * 2 * RGB.get()];
+        assert_eq!(
+            try_blit_multi_threaded(&src, &size, &mut dst, &PositionU::default(), &size, RGB, 0),
+            Err(BlitError::ZeroThreads)
+        );
+        assert_eq!(dst, [0; 12]);
+        // More threads than bytes.
+        assert!(
+            try_blit_multi_threaded(&src, &size, &mut dst, &PositionU::default(), &size, RGB, 64)
+                .is_ok()
+        );
+        assert_eq!(dst, src);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{Insets, Rect, Size, blit_scaled_box, blit_tiled, read_rows, stride::Stride};
+
+/// How [`blit_nine_slice`] fills the edges and center of the destination rect.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SliceMode {
+    /// Scale the edges and center to fit.
+    #[default]
+    Stretch,
+    /// Repeat the edges and center, e.g. for a patterned border.
+    Tile,
+}
+
+/// Draw `src` into `dst_rect` as a nine-slice (nine-patch) image, e.g. for a UI panel or button that can be any size.
+///
+/// `insets` splits `src` into a 3×3 grid. The corners are copied unscaled, the top and bottom edges are filled horizontally,
+/// the left and right edges are filled vertically, and the center is filled in both directions, according to `mode`.
+/// If `dst_rect` is smaller than the corners, the corners are shrunk to fit.
+/// `dst_rect` is clipped to `dst_size`.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_nine_slice(
+    src: &[u8],
+    src_size: &Size,
+    insets: &Insets,
+    dst: &mut [u8],
+    dst_rect: &Rect,
+    dst_size: &Size,
+    stride: Stride,
+    mode: SliceMode,
+) {
+    let src_columns = split(src_size.w, insets.left, insets.right);
+    let src_rows = split(src_size.h, insets.top, insets.bottom);
+    let dst_columns = split(dst_rect.size.w, insets.left, insets.right);
+    let dst_rows = split(dst_rect.size.h, insets.top, insets.bottom);
+    (0..9).for_each(|i| {
+        let (column, row) = (i % 3, i / 3);
+        let src_rect = Rect::new(
+            src_columns[column].0,
+            src_rows[row].0,
+            src_columns[column].1,
+            src_rows[row].1,
+        );
+        let rect = Rect::new(
+            dst_rect.position.x + dst_columns[column].0,
+            dst_rect.position.y + dst_rows[row].0,
+            dst_columns[column].1,
+            dst_rows[row].1,
+        );
+        if src_rect.is_empty() || rect.is_empty() {
+            return;
+        }
+        let slice = read_rows(src, src_size, &src_rect, stride)
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+        match mode {
+            SliceMode::Tile => blit_tiled(&slice, &src_rect.size, dst, &rect, dst_size, stride),
+            SliceMode::Stretch => {
+                blit_scaled_box(&slice, &src_rect.size, dst, &rect, dst_size, stride)
+            }
+        }
+    });
+}
+
+/// Split `len` into the start and length of a leading inset, the middle, and a trailing inset.
+/// If the insets are longer than `len`, they're shrunk to fit and the middle is empty.
+fn split(len: usize, start: usize, end: usize) -> [(usize, usize); 3] {
+    let start = start.min(len);
+    let end = end.min(len - start);
+    let middle = len - start - end;
+    [(0, start), (start, middle), (start + middle, end)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_nine_slice() {
+        // 1 2 3
+        // 4 5 6
+        // 7 8 9
+        let src = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut dst = [0; 20];
+        blit_nine_slice(
+            &src,
+            &Size { w: 3, h: 3 },
+            &Insets::uniform(1),
+            &mut dst,
+            &Rect::new(0, 0, 5, 4),
+            &Size { w: 5, h: 4 },
+            GRAYSCALE,
+            SliceMode::Stretch,
+        );
+        assert_eq!(
+            dst,
+            [1, 2, 2, 2, 3, 4, 5, 5, 5, 6, 4, 5, 5, 5, 6, 7, 8, 8, 8, 9]
+        );
+
+        // Corners are shrunk to fit.
+        let mut dst = [0; 2];
+        blit_nine_slice(
+            &src,
+            &Size { w: 3, h: 3 },
+            &Insets::uniform(1),
+            &mut dst,
+            &Rect::new(0, 0, 1, 2),
+            &Size { w: 1, h: 2 },
+            GRAYSCALE,
+            SliceMode::Tile,
+        );
+        assert_eq!(dst, [1, 7]);
+    }
+}
@@ -0,0 +1,183 @@
+//! Seeded noise generators that write into grayscale images.
+//!
+//! Each function fills `dst`, a flat byte slice of a [`crate::stride::GRAYSCALE`] image of size `size`.
+//! The same seed always produces the same noise.
+
+use crate::Size;
+
+/// Fill `dst` with uniformly distributed random values.
+pub fn white_noise(dst: &mut [u8], size: &Size, seed: u32) {
+    fill(dst, size, |x, y| {
+        (hash(x as u32, y as u32, seed) >> 24) as u8
+    });
+}
+
+/// Fill `dst` with value noise: random values on a lattice of `cell_size`-pixel cells, smoothly interpolated.
+///
+/// If `tileable` is true, the lattice wraps around, so `dst` can be tiled seamlessly.
+/// This is exact if `size` is a multiple of `cell_size`.
+pub fn value_noise(dst: &mut [u8], size: &Size, cell_size: usize, seed: u32, tileable: bool) {
+    let lattice = Lattice::new(size, cell_size, tileable);
+    fill(dst, size, |x, y| {
+        let (x0, y0, x1, y1, tx, ty) = lattice.get(x, y);
+        let value = |x: u32, y: u32| (hash(x, y, seed) >> 24) as f32;
+        let top = lerp(value(x0, y0), value(x1, y0), smoothstep(tx));
+        let bottom = lerp(value(x0, y1), value(x1, y1), smoothstep(tx));
+        lerp(top, bottom, smoothstep(ty)).round() as u8
+    });
+}
+
+/// Fill `dst` with Perlin (gradient) noise on a lattice of `cell_size`-pixel cells.
+///
+/// If `tileable` is true, the lattice wraps around, so `dst` can be tiled seamlessly.
+/// This is exact if `size` is a multiple of `cell_size`.
+pub fn perlin_noise(dst: &mut [u8], size: &Size, cell_size: usize, seed: u32, tileable: bool) {
+    // The eight gradient directions, normalized.
+    const D: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    const GRADIENTS: [(f32, f32); 8] = [
+        (1., 0.),
+        (-1., 0.),
+        (0., 1.),
+        (0., -1.),
+        (D, D),
+        (-D, D),
+        (D, -D),
+        (-D, -D),
+    ];
+    let lattice = Lattice::new(size, cell_size, tileable);
+    fill(dst, size, |x, y| {
+        let (x0, y0, x1, y1, tx, ty) = lattice.get(x, y);
+        let dot = |x: u32, y: u32, dx: f32, dy: f32| {
+            let (gx, gy) = GRADIENTS[(hash(x, y, seed) >> 29) as usize];
+            gx * dx + gy * dy
+        };
+        let (u, v) = (fade(tx), fade(ty));
+        let top = lerp(dot(x0, y0, tx, ty), dot(x1, y0, tx - 1., ty), u);
+        let bottom = lerp(dot(x0, y1, tx, ty - 1.), dot(x1, y1, tx - 1., ty - 1.), u);
+        // The range of 2D Perlin noise is ±√½.
+        let n = lerp(top, bottom, v) / D;
+        ((n * 0.5 + 0.5).clamp(0., 1.) * 255.).round() as u8
+    });
+}
+
+/// Fill `dst` with blue-noise-like values, i.e. noise without low-frequency clumps, which is well-suited for dithering.
+///
+/// This is a cheap low-discrepancy approximation (the R2 sequence) rather than true blue noise, and it isn't tileable.
+pub fn blue_noise(dst: &mut [u8], size: &Size, seed: u32) {
+    // 1 / g and 1 / g² where g is the plastic number.
+    const A1: f64 = 0.754_877_666_246_692_8;
+    const A2: f64 = 0.569_840_290_998_053_2;
+    let offset = (hash(0, 0, seed) >> 8) as f64 / (1 << 24) as f64;
+    fill(dst, size, |x, y| {
+        let v = (offset + x as f64 * A1 + y as f64 * A2).fract();
+        // Fold into a triangle wave so that neighbors don't form visible diagonal ramps.
+        let v = if v < 0.5 { v * 2. } else { 2. - v * 2. };
+        (v * 255.).round() as u8
+    });
+}
+
+/// Lattice cell coordinates for value and gradient noise.
+struct Lattice {
+    cell_size: f32,
+    /// If tileable, the number of cells per row and column.
+    period: Option<(u32, u32)>,
+}
+
+impl Lattice {
+    fn new(size: &Size, cell_size: usize, tileable: bool) -> Self {
+        let cell_size = cell_size.max(1);
+        let period = tileable.then(|| {
+            (
+                size.w.div_ceil(cell_size).max(1) as u32,
+                size.h.div_ceil(cell_size).max(1) as u32,
+            )
+        });
+        Self {
+            cell_size: cell_size as f32,
+            period,
+        }
+    }
+
+    /// Returns the corners of the cell containing `(x, y)` and the fractional position within the cell.
+    fn get(&self, x: usize, y: usize) -> (u32, u32, u32, u32, f32, f32) {
+        let fx = x as f32 / self.cell_size;
+        let fy = y as f32 / self.cell_size;
+        let (x0, y0) = (fx as u32, fy as u32);
+        let (x1, y1) = match self.period {
+            Some((pw, ph)) => ((x0 + 1) % pw, (y0 + 1) % ph),
+            None => (x0 + 1, y0 + 1),
+        };
+        (x0, y0, x1, y1, fx.fract(), fy.fract())
+    }
+}
+
+fn fill<F: Fn(usize, usize) -> u8>(dst: &mut [u8], size: &Size, f: F) {
+    if size.w > 0 {
+        dst[..size.w * size.h]
+            .chunks_exact_mut(size.w)
+            .enumerate()
+            .for_each(|(y, row)| {
+                row.iter_mut()
+                    .enumerate()
+                    .for_each(|(x, value)| *value = f(x, y))
+            });
+    }
+}
+
+/// A cheap, well-mixed integer hash of a 2D coordinate and a seed.
+const fn hash(x: u32, y: u32, seed: u32) -> u32 {
+    let mut h = seed ^ x.wrapping_mul(0x27d4_eb2d) ^ y.wrapping_mul(0x1656_67b1);
+    h = (h ^ (h >> 15)).wrapping_mul(0x85eb_ca6b);
+    h = (h ^ (h >> 13)).wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3. - 2. * t)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Generator<'a> = &'a dyn Fn(&mut [u8], u32);
+
+    #[test]
+    fn test_noise() {
+        let size = Size { w: 64, h: 32 };
+        let generators: [Generator; 4] = [
+            &|dst, seed| white_noise(dst, &size, seed),
+            &|dst, seed| value_noise(dst, &size, 8, seed, true),
+            &|dst, seed| perlin_noise(dst, &size, 8, seed, false),
+            &|dst, seed| blue_noise(dst, &size, seed),
+        ];
+        generators.iter().for_each(|generate| {
+            let mut a = vec![0u8; size.w * size.h];
+            let mut b = vec![0u8; size.w * size.h];
+            generate(&mut a, 1);
+            generate(&mut b, 1);
+            assert_eq!(a, b);
+            generate(&mut b, 2);
+            assert_ne!(a, b);
+            let min = *a.iter().min().unwrap();
+            let max = *a.iter().max().unwrap();
+            assert!(max - min > 64);
+        });
+    }
+
+    #[test]
+    fn test_tileable() {
+        // With a tileable lattice, the right edge wraps to the left edge's lattice values.
+        let lattice = Lattice::new(&Size { w: 32, h: 32 }, 8, true);
+        let (_, _, x1, y1, _, _) = lattice.get(31, 31);
+        assert_eq!((x1, y1), (0, 0));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    PositionU, Size, get_index,
+    stride::{RGB565, RGBA, Stride},
+};
+
+/// A 16-bit packed pixel format, e.g. for an embedded display or a retro framebuffer.
+///
+/// Pixels are stored little-endian, with the first channel in the most significant bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Packed16 {
+    /// See [`crate::stride::RGB565`].
+    Rgb565,
+    /// See [`crate::stride::RGB555`].
+    Rgb555,
+    /// See [`crate::stride::RGBA4444`].
+    Rgba4444,
+}
+
+impl Packed16 {
+    /// The per-pixel stride, which is always 2.
+    pub const fn stride(&self) -> Stride {
+        RGB565
+    }
+
+    /// Pack the RGBA color `rgba`, rounding each channel to the nearest representable value.
+    /// Formats without alpha drop it.
+    pub const fn pack(&self, rgba: [u8; 4]) -> u16 {
+        let [r, g, b, a] = rgba;
+        match self {
+            Self::Rgb565 => (quantize(r, 5) << 11) | (quantize(g, 6) << 5) | quantize(b, 5),
+            Self::Rgb555 => (quantize(r, 5) << 10) | (quantize(g, 5) << 5) | quantize(b, 5),
+            Self::Rgba4444 => {
+                (quantize(r, 4) << 12)
+                    | (quantize(g, 4) << 8)
+                    | (quantize(b, 4) << 4)
+                    | quantize(a, 4)
+            }
+        }
+    }
+
+    /// Unpack `value` to RGBA, expanding each channel to the full `[0, 255]` range. Formats without alpha are opaque.
+    pub const fn unpack(&self, value: u16) -> [u8; 4] {
+        match self {
+            Self::Rgb565 => [
+                expand(value >> 11, 5),
+                expand(value >> 5, 6),
+                expand(value, 5),
+                255,
+            ],
+            Self::Rgb555 => [
+                expand(value >> 10, 5),
+                expand(value >> 5, 5),
+                expand(value, 5),
+                255,
+            ],
+            Self::Rgba4444 => [
+                expand(value >> 12, 4),
+                expand(value >> 8, 4),
+                expand(value >> 4, 4),
+                expand(value, 4),
+            ],
+        }
+    }
+}
+
+/// Blit the RGBA image `src` onto `dst`, a 16-bit packed image, packing each pixel to `format` during the copy.
+pub fn blit_pack_16(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    format: Packed16,
+) {
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, RGBA);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, RGB565);
+        src[src_index..src_index + src_size.w * RGBA]
+            .chunks_exact(RGBA.get())
+            .zip(dst[dst_index..dst_index + src_size.w * RGB565].chunks_exact_mut(2))
+            .for_each(|(s, d)| {
+                d.copy_from_slice(&format.pack([s[0], s[1], s[2], s[3]]).to_le_bytes())
+            });
+    });
+}
+
+/// Blit `src`, a 16-bit packed image in `format`, onto the RGBA image `dst`, unpacking each pixel during the copy.
+pub fn blit_unpack_16(
+    src: &[u8],
+    src_size: &Size,
+    format: Packed16,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+) {
+    (0..src_size.h).for_each(|src_y| {
+        let src_index = get_index(0, src_y, src_size.w, RGB565);
+        let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, RGBA);
+        src[src_index..src_index + src_size.w * RGB565]
+            .chunks_exact(2)
+            .zip(dst[dst_index..dst_index + src_size.w * RGBA].chunks_exact_mut(RGBA.get()))
+            .for_each(|(s, d)| d.copy_from_slice(&format.unpack(u16::from_le_bytes([s[0], s[1]]))));
+    });
+}
+
+/// Round the 8-bit channel `c` to `bits` bits.
+const fn quantize(c: u8, bits: u32) -> u16 {
+    let max = (1 << bits) - 1;
+    ((c as u32 * max + 127) / 255) as u16
+}
+
+/// Expand the low `bits` bits of `value` to 8 bits.
+const fn expand(value: u16, bits: u32) -> u8 {
+    let max = (1 << bits) - 1;
+    (((value as u32 & max) * 255 + max / 2) / max) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_16() {
+        assert_eq!(Packed16::Rgb565.pack([255, 0, 255, 0]), 0xF81F);
+        assert_eq!(Packed16::Rgb555.pack([0, 255, 0, 0]), 0x03E0);
+        assert_eq!(Packed16::Rgba4444.pack([255, 0, 0, 136]), 0xF008);
+
+        let src = [255, 128, 0, 255, 17, 34, 51, 68];
+        let size = Size { w: 2, h: 1 };
+        let mut packed = [0; 4];
+        blit_pack_16(
+            &src,
+            &size,
+            &mut packed,
+            &PositionU::default(),
+            &size,
+            Packed16::Rgba4444,
+        );
+        let mut unpacked = [0; 8];
+        blit_unpack_16(
+            &packed,
+            &size,
+            Packed16::Rgba4444,
+            &mut unpacked,
+            &PositionU::default(),
+            &size,
+        );
+        assert_eq!(unpacked, [255, 136, 0, 255, 17, 34, 51, 68]);
+    }
+}
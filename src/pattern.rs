@@ -0,0 +1,193 @@
+use crate::{BlendMode, Overwrite, Rect, Size, get_index, stride::Stride};
+
+/// A procedural, infinitely repeating two-color pattern. All lengths are in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// Vertical stripes. Each stripe is `width` pixels wide and the foreground and background alternate.
+    Stripes { width: f32 },
+    /// A checkerboard of `cell`-pixel squares.
+    Checker { cell: f32 },
+    /// Foreground lines `line_width` pixels wide, spaced `cell` pixels apart.
+    Grid { cell: f32, line_width: f32 },
+    /// Foreground circles of `radius` pixels, centered in each `cell`-pixel square.
+    Dots { cell: f32, radius: f32 },
+}
+
+impl Pattern {
+    /// Returns true if the pattern-space point `(u, v)` is in the foreground.
+    fn is_foreground(&self, u: f32, v: f32) -> bool {
+        match *self {
+            Self::Stripes { width } => (u / width).floor().rem_euclid(2.) == 0.,
+            Self::Checker { cell } => {
+                ((u / cell).floor() + (v / cell).floor()).rem_euclid(2.) == 0.
+            }
+            Self::Grid { cell, line_width } => {
+                u.rem_euclid(cell) < line_width || v.rem_euclid(cell) < line_width
+            }
+            Self::Dots { cell, radius } => {
+                let du = u.rem_euclid(cell) - cell / 2.;
+                let dv = v.rem_euclid(cell) - cell / 2.;
+                du * du + dv * dv < radius * radius
+            }
+        }
+    }
+}
+
+/// How a [`Pattern`] is scaled and rotated about the destination image's origin.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PatternTransform {
+    /// A multiplier applied to every length in the pattern.
+    pub scale: f32,
+    /// The clockwise rotation in radians.
+    pub rotation: f32,
+}
+
+impl Default for PatternTransform {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            rotation: 0.,
+        }
+    }
+}
+
+/// Fill `rect` in `dst` with `pattern`, without materializing a source image.
+///
+/// - `dst` is a flat byte slice of an image of size `dst_size`.
+/// - `foreground` and `background` are single pixels. Their length is the per-pixel stride.
+///
+/// The pattern is anchored to the origin of `dst` rather than `rect`, so adjacent fills line up seamlessly.
+/// `rect` is clipped to `dst_size`. If `foreground` and `background` aren't the same length, `dst` is unchanged.
+pub fn fill_pattern(
+    dst: &mut [u8],
+    dst_size: &Size,
+    rect: &Rect,
+    pattern: &Pattern,
+    transform: &PatternTransform,
+    foreground: &[u8],
+    background: &[u8],
+) {
+    blend_pattern(
+        dst, dst_size, rect, pattern, transform, foreground, background, &Overwrite,
+    );
+}
+
+/// Blend `pattern` onto `rect` in `dst`, combining each pattern pixel with the destination pixel using `mode`,
+/// e.g. [`crate::CompositeOp::SrcOver`] with semi-transparent RGBA colors to draw hatching over an image.
+///
+/// This is the same as [`fill_pattern`] except for the blend mode. `fill_pattern` is `blend_pattern` with [`Overwrite`].
+#[allow(clippy::too_many_arguments)]
+pub fn blend_pattern<B: BlendMode>(
+    dst: &mut [u8],
+    dst_size: &Size,
+    rect: &Rect,
+    pattern: &Pattern,
+    transform: &PatternTransform,
+    foreground: &[u8],
+    background: &[u8],
+    mode: &B,
+) {
+    let Some(stride) = Stride::new(foreground.len()) else {
+        return;
+    };
+    if background.len() != foreground.len() {
+        return;
+    }
+    let x1 = rect.right().min(dst_size.w);
+    let y1 = rect.bottom().min(dst_size.h);
+    if rect.position.x >= x1 || rect.position.y >= y1 {
+        return;
+    }
+    // Map destination pixels back into pattern space.
+    let (sin, cos) = (-transform.rotation).sin_cos();
+    let inverse_scale = 1. / transform.scale;
+    (rect.position.y..y1).for_each(|y| {
+        let index = get_index(rect.position.x, y, dst_size.w, stride);
+        dst[index..index + (x1 - rect.position.x) * stride]
+            .chunks_exact_mut(stride.get())
+            .zip(rect.position.x..x1)
+            .for_each(|(pixel, x)| {
+                // Sample the center of the pixel.
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let u = (px * cos - py * sin) * inverse_scale;
+                let v = (px * sin + py * cos) * inverse_scale;
+                let color = if pattern.is_foreground(u, v) {
+                    foreground
+                } else {
+                    background
+                };
+                mode.blend(color, pixel);
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompositeOp, stride::GRAYSCALE};
+
+    #[test]
+    fn test_fill_pattern() {
+        let dst_size = Size { w: 8, h: 8 };
+        let mut dst = vec![0u8; 8 * 8 * GRAYSCALE.get()];
+        let rect = Rect::new(0, 0, 8, 8);
+        fill_pattern(
+            &mut dst,
+            &dst_size,
+            &rect,
+            &Pattern::Checker { cell: 2. },
+            &PatternTransform::default(),
+            &[255],
+            &[0],
+        );
+        assert_eq!(dst[0..8], [255, 255, 0, 0, 255, 255, 0, 0]);
+        assert_eq!(dst[16..24], [0, 0, 255, 255, 0, 0, 255, 255]);
+
+        // Rotating vertical stripes by 90° makes horizontal stripes.
+        fill_pattern(
+            &mut dst,
+            &dst_size,
+            &rect,
+            &Pattern::Stripes { width: 1. },
+            &PatternTransform {
+                scale: 2.,
+                rotation: std::f32::consts::FRAC_PI_2,
+            },
+            &[255],
+            &[0],
+        );
+        (0..8).for_each(|y| {
+            let row = &dst[y * 8..y * 8 + 8];
+            assert!(row.iter().all(|value| *value == row[0]));
+        });
+        assert_ne!(dst[0], dst[16]);
+
+        // Half-transparent white stripes over opaque black.
+        let dst_size = Size { w: 2, h: 1 };
+        let mut dst = [0, 0, 0, 255, 0, 0, 0, 255];
+        blend_pattern(
+            &mut dst,
+            &dst_size,
+            &Rect::new(0, 0, 2, 1),
+            &Pattern::Stripes { width: 1. },
+            &PatternTransform::default(),
+            &[255, 255, 255, 128],
+            &[0, 0, 0, 0],
+            &CompositeOp::SrcOver,
+        );
+        assert_eq!(dst, [128, 128, 128, 255, 0, 0, 0, 255]);
+
+        // The foreground and background must be the same length.
+        let mut dst = [7; 2];
+        fill_pattern(
+            &mut dst,
+            &dst_size,
+            &Rect::new(0, 0, 2, 1),
+            &Pattern::Stripes { width: 1. },
+            &PatternTransform::default(),
+            &[255],
+            &[],
+        );
+        assert_eq!(dst, [7; 2]);
+    }
+}
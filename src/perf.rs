@@ -0,0 +1,78 @@
+//! Counters of how many blits took each code path, to check that real workloads hit the fast paths.
+//!
+//! The counters are always enabled. Each blit increments one relaxed atomic, which is negligible next to copying pixels.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ROWS: AtomicU64 = AtomicU64::new(0);
+static BLEND: AtomicU64 = AtomicU64::new(0);
+static THREADED: AtomicU64 = AtomicU64::new(0);
+
+/// The code path that a blit took.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Strategy {
+    Rows,
+    Blend,
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    Threaded,
+}
+
+/// The number of blits that took each code path since the last [`reset`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// Blits that were copied row by row.
+    pub rows: u64,
+    /// Blits that blended each pixel.
+    pub blend: u64,
+    /// Blits that were split across threads.
+    pub threaded: u64,
+}
+
+/// Returns the current counts.
+pub fn snapshot() -> Counters {
+    Counters {
+        rows: ROWS.load(Ordering::Relaxed),
+        blend: BLEND.load(Ordering::Relaxed),
+        threaded: THREADED.load(Ordering::Relaxed),
+    }
+}
+
+/// Set all of the counts to zero.
+pub fn reset() {
+    [&ROWS, &BLEND, &THREADED]
+        .iter()
+        .for_each(|counter| counter.store(0, Ordering::Relaxed));
+}
+
+pub(crate) fn record(strategy: Strategy) {
+    match strategy {
+        Strategy::Rows => &ROWS,
+        Strategy::Blend => &BLEND,
+        Strategy::Threaded => &THREADED,
+    }
+    .fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PositionU, Size, blit, stride::GRAYSCALE};
+
+    #[test]
+    fn test_perf_counters() {
+        // Other tests blit concurrently, so only check that the counts went up.
+        let before = snapshot();
+        let size = Size { w: 2, h: 2 };
+        let mut dst = [0; 8];
+        blit(
+            &[1; 4],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &Size { w: 4, h: 2 },
+            GRAYSCALE,
+        );
+        let after = snapshot();
+        assert!(after.rows > before.rows);
+    }
+}
@@ -0,0 +1,290 @@
+use std::marker::PhantomData;
+
+use crate::{
+    BlendMode, Convert, Format, Resample,
+    blend::{alpha_to_u8, lerp, source_over},
+    stride::{GRAYSCALE, RGB, RGBA, Stride},
+};
+
+/// A pixel type that can be read from and written to a flat byte slice, and blended.
+///
+/// Implement this for custom pixel formats (e.g. 10:10:10:2 or Lab f32) to blend them with [`crate::blit_mode`] and [`PixelOver`] or [`PixelLerp`],
+/// and to scale and convert them with [`PixelType`].
+pub trait Pixel: Copy {
+    /// The number of bytes per pixel.
+    const STRIDE: Stride;
+
+    /// Read a pixel from the first [`Pixel::STRIDE`] bytes of `bytes`.
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    /// Write the pixel to the first [`Pixel::STRIDE`] bytes of `bytes`.
+    fn to_bytes(&self, bytes: &mut [u8]);
+
+    /// Linearly interpolate from `self` to `other`, where `t` is between 0 and 1.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+
+    /// Composite `self` over `dst`. For formats without alpha, this returns `self`.
+    fn over(&self, dst: &Self) -> Self;
+}
+
+/// A 1-byte grayscale pixel.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Gray8(pub u8);
+
+/// A 3-byte RGB pixel.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rgb8(pub [u8; 3]);
+
+/// A 4-byte RGBA pixel with straight (not premultiplied) alpha.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rgba8(pub [u8; 4]);
+
+impl Pixel for Gray8 {
+    const STRIDE: Stride = GRAYSCALE;
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes[0])
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.0;
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self(lerp(self.0, other.0, alpha_to_u8(t)))
+    }
+
+    fn over(&self, _: &Self) -> Self {
+        *self
+    }
+}
+
+impl Pixel for Rgb8 {
+    const STRIDE: Stride = RGB;
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self([bytes[0], bytes[1], bytes[2]])
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[..3].copy_from_slice(&self.0);
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = alpha_to_u8(t);
+        Self(std::array::from_fn(|i| lerp(self.0[i], other.0[i], t)))
+    }
+
+    fn over(&self, _: &Self) -> Self {
+        *self
+    }
+}
+
+impl Pixel for Rgba8 {
+    const STRIDE: Stride = RGBA;
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&self.0);
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = alpha_to_u8(t);
+        Self(std::array::from_fn(|i| lerp(self.0[i], other.0[i], t)))
+    }
+
+    fn over(&self, dst: &Self) -> Self {
+        let mut out = dst.0;
+        source_over(&self.0, &mut out, 255);
+        Self(out)
+    }
+}
+
+impl From<Gray8> for Rgb8 {
+    fn from(value: Gray8) -> Self {
+        Self([value.0; 3])
+    }
+}
+
+impl From<Rgb8> for Rgba8 {
+    fn from(value: Rgb8) -> Self {
+        let [r, g, b] = value.0;
+        Self([r, g, b, 255])
+    }
+}
+
+/// Composite each source pixel over its destination pixel with [`Pixel::over`].
+///
+/// Pass this to [`crate::blit_mode`] with a stride of `P::STRIDE`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PixelOver<P: Pixel>(PhantomData<P>);
+
+impl<P: Pixel> PixelOver<P> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: Pixel> BlendMode for PixelOver<P> {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        P::from_bytes(src).over(&P::from_bytes(dst)).to_bytes(dst);
+    }
+}
+
+/// Interpolate each destination pixel towards its source pixel by `t`, between 0 and 1, with [`Pixel::lerp`].
+///
+/// This is a crossfade: 0 leaves `dst` unchanged and 1 is the same as a copy.
+/// Pass this to [`crate::blit_mode`] with a stride of `P::STRIDE`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PixelLerp<P: Pixel> {
+    pub t: f32,
+    pixel: PhantomData<P>,
+}
+
+impl<P: Pixel> PixelLerp<P> {
+    pub const fn new(t: f32) -> Self {
+        Self {
+            t,
+            pixel: PhantomData,
+        }
+    }
+}
+
+impl<P: Pixel> BlendMode for PixelLerp<P> {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        P::from_bytes(dst)
+            .lerp(&P::from_bytes(src), self.t)
+            .to_bytes(dst);
+    }
+}
+
+/// Scale or convert images of `P` pixels by passing this in place of a stride to [`crate::blit_scaled_box`] or [`crate::blit_scaled_bilinear`],
+/// or in place of a [`crate::PixelFormat`] to [`crate::blit_convert`].
+///
+/// Pixels are filtered with [`Pixel::lerp`] and converted with [`From`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PixelType<P: Pixel>(PhantomData<P>);
+
+impl<P: Pixel> PixelType<P> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: Pixel> Resample for PixelType<P> {
+    fn stride(&self) -> Stride {
+        P::STRIDE
+    }
+
+    fn average<'a>(&self, mut pixels: impl Iterator<Item = &'a [u8]> + Clone, dst: &mut [u8]) {
+        if let Some(first) = pixels.next() {
+            // A running mean: the nth pixel is weighted by 1/n.
+            pixels
+                .enumerate()
+                .fold(P::from_bytes(first), |mean, (i, pixel)| {
+                    mean.lerp(&P::from_bytes(pixel), 1. / (i + 2) as f32)
+                })
+                .to_bytes(dst);
+        }
+    }
+
+    fn bilinear(
+        &self,
+        top: [&[u8]; 2],
+        bottom: [&[u8]; 2],
+        weight_x: u32,
+        weight_y: u32,
+        dst: &mut [u8],
+    ) {
+        let mix = |[left, right]: [&[u8]; 2]| {
+            P::from_bytes(left).lerp(&P::from_bytes(right), weight_x as f32 / 256.)
+        };
+        mix(top)
+            .lerp(&mix(bottom), weight_y as f32 / 256.)
+            .to_bytes(dst);
+    }
+}
+
+impl<P: Pixel> Format for PixelType<P> {
+    fn stride(&self) -> Stride {
+        P::STRIDE
+    }
+}
+
+impl<S: Pixel, D: Pixel + From<S>> Convert<PixelType<D>> for PixelType<S> {
+    #[inline]
+    fn convert(&self, _: &PixelType<D>, src: &[u8], dst: &mut [u8]) {
+        D::from(S::from_bytes(src)).to_bytes(dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PositionU, Rect, Size, blit_convert, blit_mode, blit_scaled_bilinear};
+
+    #[test]
+    fn test_pixel_modes() {
+        let src = [255, 0, 0, 128, 0, 255, 0, 0];
+        let src_size = Size { w: 2, h: 1 };
+        let mut dst = [0, 0, 255, 255, 0, 0, 255, 255];
+        blit_mode(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            Rgba8::STRIDE,
+            &PixelOver::<Rgba8>::new(),
+        );
+        assert_eq!(dst, [128, 0, 127, 255, 0, 0, 255, 255]);
+
+        let src = [200, 100];
+        let mut dst = [0, 0, 0];
+        blit_mode(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &Size { w: 3, h: 1 },
+            Gray8::STRIDE,
+            &PixelLerp::<Gray8>::new(0.5),
+        );
+        assert_eq!(dst, [0, 100, 50]);
+
+        assert_eq!(Rgba8::from(Rgb8::from(Gray8(7))), Rgba8([7, 7, 7, 255]));
+    }
+
+    #[test]
+    fn test_pixel_type() {
+        let src = [0, 200];
+        let src_size = Size { w: 2, h: 1 };
+        let mut dst = [9; 4];
+        blit_scaled_bilinear(
+            &src,
+            &src_size,
+            &mut dst,
+            &Rect::new(0, 0, 4, 1),
+            &Size { w: 4, h: 1 },
+            PixelType::<Gray8>::new(),
+        );
+        assert_eq!(dst, [0, 50, 150, 200]);
+
+        let mut dst = [0; 8];
+        blit_convert(
+            &src,
+            &src_size,
+            PixelType::<Gray8>::new(),
+            &mut dst,
+            &PositionU::default(),
+            &src_size,
+            PixelType::<Rgb8>::new(),
+        );
+        assert_eq!(dst, [0, 0, 0, 200, 200, 200, 0, 0]);
+    }
+}
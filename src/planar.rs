@@ -0,0 +1,146 @@
+use crate::{PositionU, Rect, Size, blit_rop_1bpp, rop::Rop, stride::RGB};
+
+/// The length in bytes of one 1bpp bitplane of an image of size `size`. Each row starts on a new byte.
+pub const fn plane_len(size: &Size) -> usize {
+    size.w.div_ceil(8) * size.h
+}
+
+/// Blit the planar image `src` onto the planar image `dst`. Both have `planes` bitplanes.
+///
+/// A planar image is `planes` 1bpp bitplanes stored one after another, each [`plane_len`] bytes long.
+/// Plane 0 holds the least significant bit of each pixel's color index. See [`blit_rop_1bpp`] for the bit layout.
+pub fn blit_planar(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    planes: usize,
+) {
+    let (src_len, dst_len) = (plane_len(src_size), plane_len(dst_size));
+    (0..planes).for_each(|plane| {
+        blit_rop_1bpp(
+            &src[plane * src_len..(plane + 1) * src_len],
+            src_size,
+            &mut dst[plane * dst_len..(plane + 1) * dst_len],
+            dst_position,
+            dst_size,
+            Rop::Copy,
+        );
+    });
+}
+
+/// Fill `rect` in the planar image `dst` with the color index `index`, setting or clearing the bits of each of its `planes` bitplanes.
+///
+/// `rect` is clipped to `dst_size`.
+pub fn fill_rect_planar(dst: &mut [u8], dst_size: &Size, planes: usize, rect: &Rect, index: u32) {
+    let x1 = rect.right().min(dst_size.w);
+    let y1 = rect.bottom().min(dst_size.h);
+    let row_len = dst_size.w.div_ceil(8);
+    let len = plane_len(dst_size);
+    (0..planes).for_each(|plane| {
+        let set = (index >> plane) & 1 == 1;
+        let dst = &mut dst[plane * len..(plane + 1) * len];
+        (rect.position.y..y1).for_each(|y| {
+            (rect.position.x..x1).for_each(|x| set_bit(&mut dst[y * row_len..], x, set));
+        });
+    });
+}
+
+/// Convert the planar image `src` with `planes` bitplanes to chunky RGB by looking up each pixel's color index in `palette`.
+///
+/// `dst` is an RGB image of size `size`. Indices past the end of `palette` are black.
+pub fn planar_to_rgb(src: &[u8], size: &Size, planes: usize, palette: &[[u8; 3]], dst: &mut [u8]) {
+    let row_len = size.w.div_ceil(8);
+    let len = plane_len(size);
+    dst.chunks_exact_mut(RGB.get())
+        .take(size.w * size.h)
+        .enumerate()
+        .for_each(|(i, pixel)| {
+            let (x, y) = (i % size.w, i / size.w);
+            let index = (0..planes).fold(0, |index, plane| {
+                let byte = src[plane * len + y * row_len + x / 8];
+                index | ((((byte >> (7 - x % 8)) & 1) as usize) << plane)
+            });
+            pixel.copy_from_slice(palette.get(index).unwrap_or(&[0; 3]));
+        });
+}
+
+/// Convert the chunky RGB image `src` to a planar image with `planes` bitplanes,
+/// using the index of the nearest color in `palette` for each pixel.
+///
+/// `dst` must be at least `planes * plane_len(size)` bytes long.
+pub fn rgb_to_planar(src: &[u8], size: &Size, palette: &[[u8; 3]], planes: usize, dst: &mut [u8]) {
+    let row_len = size.w.div_ceil(8);
+    let len = plane_len(size);
+    dst[..planes * len].fill(0);
+    src.chunks_exact(RGB.get())
+        .take(size.w * size.h)
+        .enumerate()
+        .for_each(|(i, pixel)| {
+            let (x, y) = (i % size.w, i / size.w);
+            let index = nearest(pixel, palette);
+            (0..planes).for_each(|plane| {
+                if (index >> plane) & 1 == 1 {
+                    set_bit(&mut dst[plane * len + y * row_len..], x, true);
+                }
+            });
+        });
+}
+
+/// Set or clear bit `x` of a row of packed 1bpp pixels.
+fn set_bit(row: &mut [u8], x: usize, set: bool) {
+    let mask = 0x80 >> (x % 8);
+    if set {
+        row[x / 8] |= mask;
+    } else {
+        row[x / 8] &= !mask;
+    }
+}
+
+/// Returns the index of the color in `palette` that is nearest to `pixel`.
+fn nearest(pixel: &[u8], palette: &[[u8; 3]]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, color)| {
+            color
+                .iter()
+                .zip(pixel)
+                .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+                .sum::<i32>()
+        })
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planar() {
+        let palette = [[0, 0, 0], [255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        let size = Size { w: 4, h: 1 };
+        let rgb = [0, 0, 0, 250, 0, 0, 0, 255, 0, 0, 0, 255];
+        let mut planar = [0u8; 2];
+        rgb_to_planar(&rgb, &size, &palette, 2, &mut planar);
+        assert_eq!(planar, [0b0101_0000, 0b0011_0000]);
+
+        let mut dst_planar = [0u8; 4];
+        let dst_size = Size { w: 10, h: 1 };
+        blit_planar(
+            &planar,
+            &size,
+            &mut dst_planar,
+            &PositionU { x: 6, y: 0 },
+            &dst_size,
+            2,
+        );
+        fill_rect_planar(&mut dst_planar, &dst_size, 2, &Rect::new(0, 0, 2, 1), 3);
+        let mut dst_rgb = [0u8; 10 * RGB.get()];
+        planar_to_rgb(&dst_planar, &dst_size, 2, &palette, &mut dst_rgb);
+        assert_eq!(dst_rgb[..6], [0, 0, 255, 0, 0, 255]);
+        assert_eq!(dst_rgb[18..], [0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255]);
+    }
+}
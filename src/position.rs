@@ -1,11 +1,12 @@
 /// A signed `(x, y)` pixel position.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct PositionI {
     pub x: isize,
     pub y: isize,
 }
 
 /// An unsigned `(x, y)` pixel position.
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct PositionU {
     pub x: usize,
     pub y: usize,
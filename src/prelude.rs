@@ -0,0 +1,20 @@
+//! The types and functions that most programs need, in one import.
+//!
+//! ```
+//! use blittle::prelude::*;
+//! ```
+//!
+//! The prelude is the crate's stable surface: geometry, strides, the core blit functions, and the enums that select how pixels are combined.
+//! Items are only added to it, not removed or renamed, outside of a breaking release.
+//! Everything else, including specialized helpers such as [`crate::noise`] or [`crate::perf`], is still available from the crate root or its own module.
+
+pub use crate::{
+    Anchor, BlendMode, BlitError, ClipResult, CompositeOp, Edge, FitMode, MaskMode, Overwrite,
+    PositionI, PositionU, Rect, Rop, Size, SizeError, StippleMode, blit, blit_blend,
+    blit_clip_result, blit_clipped, blit_composite, blit_mode, blit_region, clip, get_index,
+    present_fit, resolve_position,
+    stride::{GRAYSCALE, RGB, RGB_F32, RGBA, RGBA_F32, Stride},
+    try_blit,
+};
+#[cfg(feature = "rayon")]
+pub use crate::{blit_multi_threaded, try_blit_multi_threaded};
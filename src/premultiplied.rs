@@ -0,0 +1,96 @@
+use crate::{BlendMode, PositionU, Size, blend::mul_div_255, blit_mode, stride::RGBA};
+
+/// Convert the straight-alpha RGBA pixels in `pixels` to premultiplied alpha in place, i.e. multiply each color by its alpha.
+pub fn premultiply(pixels: &mut [u8]) {
+    pixels.chunks_exact_mut(RGBA.get()).for_each(|pixel| {
+        let a = pixel[3];
+        (0..3).for_each(|i| pixel[i] = mul_div_255(pixel[i], a));
+    });
+}
+
+/// Convert the premultiplied RGBA pixels in `pixels` to straight alpha in place, i.e. divide each color by its alpha.
+///
+/// Fully transparent pixels become transparent black. Precision that was lost when premultiplying can't be restored.
+pub fn unpremultiply(pixels: &mut [u8]) {
+    pixels
+        .chunks_exact_mut(RGBA.get())
+        .for_each(|pixel| match pixel[3] {
+            0 => pixel[..3].fill(0),
+            255 => {}
+            a => (0..3).for_each(|i| {
+                pixel[i] = ((pixel[i] as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8
+            }),
+        });
+}
+
+/// Blit the premultiplied RGBA image `src` onto the premultiplied RGBA image `dst` using source-over alpha blending.
+///
+/// This is the same as [`crate::blit_blend`] except that both images are premultiplied, as most GPU pipelines and window compositors expect.
+/// Each channel is `src + dst * (1 - src_alpha)`, which needs no division, so this is faster than blending straight alpha.
+///
+/// `opacity` scales every source pixel, e.g. to fade a sprite in or out. 255 is fully opaque.
+pub fn blit_blend_premultiplied(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    opacity: u8,
+) {
+    blit_mode(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        RGBA,
+        &PremultipliedOver { opacity },
+    );
+}
+
+/// Source-over blending of premultiplied pixels, with every source pixel scaled by `opacity`.
+struct PremultipliedOver {
+    opacity: u8,
+}
+
+impl BlendMode for PremultipliedOver {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        let sa = mul_div_255(src[3], self.opacity);
+        match sa {
+            0 => {}
+            255 => dst.copy_from_slice(src),
+            _ => (0..4).for_each(|i| {
+                dst[i] =
+                    mul_div_255(src[i], self.opacity).saturating_add(mul_div_255(dst[i], 255 - sa))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_premultiplied() {
+        let mut pixels = [255, 128, 0, 128, 10, 20, 30, 0, 1, 2, 3, 255];
+        premultiply(&mut pixels);
+        assert_eq!(pixels, [128, 64, 0, 128, 0, 0, 0, 0, 1, 2, 3, 255]);
+        unpremultiply(&mut pixels);
+        assert_eq!(pixels, [255, 128, 0, 128, 0, 0, 0, 0, 1, 2, 3, 255]);
+
+        // Half-transparent red over opaque blue.
+        let size = Size { w: 1, h: 1 };
+        let mut dst = [0, 0, 255, 255];
+        blit_blend_premultiplied(
+            &[128, 0, 0, 128],
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            255,
+        );
+        assert_eq!(dst, [128, 0, 127, 255]);
+    }
+}
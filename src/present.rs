@@ -0,0 +1,280 @@
+use crate::{
+    PositionI, Rect, Size,
+    blend::{alpha_to_u8, blend_bytes},
+    blit_clip_result, clip, fill_rect,
+    perf::{self, Strategy},
+    scale::box_resample,
+    span::fill_row,
+    stride::Stride,
+    thumbnail::fit_size,
+};
+
+/// How to fill the pixels of a frame that are uncovered when it's offset.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edge<'a> {
+    /// Fill with a single pixel. Its length must be the per-pixel stride.
+    Fill(&'a [u8]),
+    /// Repeat the nearest edge pixel of the frame.
+    Clamp,
+}
+
+/// Copy the composited frame `src` to `dst`, shifted by `offset` pixels, e.g. for screen-shake.
+///
+/// `src` and `dst` are both of size `size`. Pixels of `dst` that aren't covered by the shifted frame are filled according to `edge`.
+/// If `edge` is [`Edge::Fill`] and the color's length isn't `stride`, `dst` is unchanged.
+pub fn present_offset(
+    src: &[u8],
+    dst: &mut [u8],
+    size: &Size,
+    stride: Stride,
+    offset: &PositionI,
+    edge: Edge,
+) {
+    if size.w == 0 || size.h == 0 {
+        return;
+    }
+    if matches!(edge, Edge::Fill(color) if color.len() != stride.get()) {
+        return;
+    }
+    let (w, h) = (size.w.cast_signed(), size.h.cast_signed());
+    let row_len = size.w * stride;
+    // The range of destination columns that are covered by the source.
+    let x0 = offset.x.clamp(0, w).cast_unsigned();
+    let x1 = (offset.x + w).clamp(0, w).cast_unsigned();
+    dst.chunks_exact_mut(row_len)
+        .take(size.h)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let src_y = y.cast_signed() - offset.y;
+            let src_y = match edge {
+                Edge::Fill(color) if src_y < 0 || src_y >= h || x0 == x1 => {
+                    fill_row(row, color);
+                    return;
+                }
+                _ => src_y.clamp(0, h - 1).cast_unsigned(),
+            };
+            let src_row = &src[src_y * row_len..(src_y + 1) * row_len];
+            if x0 < x1 {
+                let src_x = (x0.cast_signed() - offset.x).cast_unsigned();
+                row[x0 * stride..x1 * stride]
+                    .copy_from_slice(&src_row[src_x * stride..(src_x + x1 - x0) * stride]);
+            }
+            let (left, right) = match edge {
+                Edge::Fill(color) => (color, color),
+                Edge::Clamp => (&src_row[..stride.get()], &src_row[row_len - stride.get()..]),
+            };
+            fill_row(&mut row[..x0 * stride], left);
+            fill_row(&mut row[x1.max(x0) * stride..], right);
+        });
+}
+
+/// How [`present_fit`] scales a frame to fit the window.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FitMode {
+    /// Scale to exactly the window size, ignoring the aspect ratio.
+    Stretch,
+    /// Scale to fit inside the window, keeping the aspect ratio. The uncovered area is filled with bars.
+    #[default]
+    Contain,
+    /// Scale to cover the whole window, keeping the aspect ratio. The frame is centered and its edges are cropped.
+    Cover,
+    /// Scale by the largest whole-number factor that fits, for pixel art. The uncovered area is filled with bars.
+    /// If the frame is larger than the window, it's centered at 1x and cropped.
+    Integer,
+}
+
+impl FitMode {
+    /// The size of a frame of size `src_size` after scaling it to fit in `dst_size`.
+    pub fn scaled_size(&self, src_size: &Size, dst_size: &Size) -> Size {
+        let (sw, sh, dw, dh) = (src_size.w, src_size.h, dst_size.w, dst_size.h);
+        if sw == 0 || sh == 0 {
+            return Size::default();
+        }
+        match self {
+            Self::Stretch => *dst_size,
+            Self::Contain => fit_size(src_size, dst_size),
+            Self::Cover => {
+                if sw * dh > sh * dw {
+                    // Wider than the window: fit to height.
+                    Size {
+                        w: (dh * sw).div_ceil(sh),
+                        h: dh,
+                    }
+                } else {
+                    Size {
+                        w: dw,
+                        h: (dw * sh).div_ceil(sw),
+                    }
+                }
+            }
+            Self::Integer => {
+                let factor = (dw / sw).min(dh / sh).max(1);
+                Size {
+                    w: sw * factor,
+                    h: sh * factor,
+                }
+            }
+        }
+    }
+}
+
+/// Scale the frame `src` to fit the window `dst` according to `mode`, centering it and filling any bars with `bar_color`.
+///
+/// - `stride` is the per-pixel stride of both `src` and `dst`.
+/// - `bar_color` is a single pixel. Its length is the per-pixel stride.
+///
+/// The frame is box-filtered when it's downscaled and nearest-neighbor sampled when it's upscaled, so pixel art stays sharp.
+/// Returns the area of `dst` that's covered by the frame.
+/// If `bar_color` isn't one pixel long, `dst` is unchanged and the returned area is empty.
+pub fn present_fit(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_size: &Size,
+    stride: Stride,
+    mode: FitMode,
+    bar_color: &[u8],
+) -> Rect {
+    if bar_color.len() != stride.get() {
+        return Rect::default();
+    }
+    let scaled_size = mode.scaled_size(src_size, dst_size);
+    let position = PositionI {
+        x: (dst_size.w.cast_signed() - scaled_size.w.cast_signed()) / 2,
+        y: (dst_size.h.cast_signed() - scaled_size.h.cast_signed()) / 2,
+    };
+    let clip = clip(&position, dst_size, &scaled_size);
+    let rect = Rect {
+        position: clip.dst_position,
+        size: clip.size,
+    };
+    // Fill the bars.
+    let (x, y) = (rect.position.x, rect.position.y);
+    [
+        Rect::new(0, 0, dst_size.w, y),
+        Rect::new(0, rect.bottom(), dst_size.w, dst_size.h - rect.bottom()),
+        Rect::new(0, y, x, rect.size.h),
+        Rect::new(rect.right(), y, dst_size.w - rect.right(), rect.size.h),
+    ]
+    .iter()
+    .filter(|bar| !bar.is_empty())
+    .for_each(|bar| fill_rect(dst, dst_size, bar, bar_color));
+    if rect.is_empty() {
+        return rect;
+    }
+    let mut scaled = vec![0; scaled_size.w * scaled_size.h * stride];
+    box_resample(src, src_size, &mut scaled, &scaled_size, stride);
+    blit_clip_result(&scaled, &scaled_size, dst, dst_size, &clip, stride);
+    rect
+}
+
+/// Blend two whole frames into `dst`, e.g. to present a smoothed frame between two fixed-rate simulation ticks.
+///
+/// - `prev`, `curr` and `dst` are flat byte slices of images of the same size and stride.
+/// - `alpha` is between 0 (`prev`) and 1 (`curr`).
+///
+/// Every byte is blended, so this works for any 8-bit-per-channel stride.
+/// Panics if the three slices aren't the same length.
+pub fn blend_frames(prev: &[u8], curr: &[u8], alpha: f32, dst: &mut [u8]) {
+    assert_frame_lens(prev, curr, dst);
+    perf::record(Strategy::Blend);
+    blend_bytes(prev, curr, alpha_to_u8(alpha), dst);
+}
+
+/// Panics if `prev`, `curr` and `dst` aren't the same length, so that a size mismatch doesn't leave part of `dst` unwritten.
+pub(crate) fn assert_frame_lens(prev: &[u8], curr: &[u8], dst: &[u8]) {
+    assert!(
+        prev.len() == dst.len() && curr.len() == dst.len(),
+        "The frames are {}, {} and {} bytes long but must be the same length",
+        prev.len(),
+        curr.len(),
+        dst.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_present_offset() {
+        let size = Size { w: 3, h: 2 };
+        let src = [1, 2, 3, 4, 5, 6];
+        let mut dst = [0; 6];
+        let offset = PositionI { x: 1, y: -1 };
+        present_offset(&src, &mut dst, &size, GRAYSCALE, &offset, Edge::Fill(&[9]));
+        assert_eq!(dst, [9, 4, 5, 9, 9, 9]);
+        present_offset(&src, &mut dst, &size, GRAYSCALE, &offset, Edge::Clamp);
+        assert_eq!(dst, [4, 4, 5, 4, 4, 5]);
+        // The fill color must be one pixel.
+        present_offset(&src, &mut dst, &size, GRAYSCALE, &offset, Edge::Fill(&[]));
+        assert_eq!(dst, [4, 4, 5, 4, 4, 5]);
+        let offset = PositionI { x: -5, y: 0 };
+        present_offset(&src, &mut dst, &size, GRAYSCALE, &offset, Edge::Clamp);
+        assert_eq!(dst, [3, 3, 3, 6, 6, 6]);
+    }
+
+    #[test]
+    fn test_present_fit() {
+        let src_size = Size { w: 2, h: 1 };
+        let src = [1, 2];
+        let dst_size = Size { w: 5, h: 4 };
+        let mut dst = [0; 20];
+        let rect = present_fit(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            GRAYSCALE,
+            FitMode::Integer,
+            &[9],
+        );
+        assert_eq!(rect, Rect::new(0, 1, 4, 2));
+        assert_eq!(dst[..10], [9, 9, 9, 9, 9, 1, 1, 2, 2, 9]);
+
+        let rect = present_fit(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            GRAYSCALE,
+            FitMode::Cover,
+            &[9],
+        );
+        assert_eq!(rect, Rect::new(0, 0, 5, 4));
+        assert_eq!(dst[..5], [1, 1, 1, 2, 2]);
+        assert_eq!(
+            FitMode::Contain.scaled_size(&src_size, &dst_size),
+            Size { w: 5, h: 2 }
+        );
+
+        // The bar color must be one pixel.
+        let mut dst = [0; 20];
+        let rect = present_fit(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            GRAYSCALE,
+            FitMode::Integer,
+            &[],
+        );
+        assert!(rect.is_empty());
+        assert_eq!(dst, [0; 20]);
+    }
+
+    #[test]
+    fn test_blend_frames() {
+        let mut dst = [0; 3];
+        blend_frames(&[0, 100, 255], &[255, 200, 255], 0.5, &mut dst);
+        assert_eq!(dst, [128, 150, 255]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_blend_frames_len() {
+        let mut dst = [0; 3];
+        blend_frames(&[0, 100], &[255, 200, 255], 0.5, &mut dst);
+    }
+}
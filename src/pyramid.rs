@@ -0,0 +1,205 @@
+use crate::{Rect, Size, read_rows, scale::box_resample, stride::Stride};
+
+/// Successively half-resolution copies of an image, for picking a suitably sized image to blit when zoomed out.
+pub struct Pyramid {
+    levels: Vec<(Vec<u8>, Size)>,
+    stride: Stride,
+}
+
+impl Pyramid {
+    /// Build a pyramid of up to `levels` levels from `src`, an image of size `src_size`.
+    ///
+    /// Level 0 is a copy of `src`, and each subsequent level is box-filtered to half the size of the previous one, rounded up.
+    /// Building stops early once a level is 1x1.
+    pub fn build(src: &[u8], src_size: &Size, stride: Stride, levels: usize) -> Self {
+        let mut pyramid = Vec::with_capacity(levels);
+        if levels > 0 {
+            pyramid.push((src[..src_size.expected_len(stride)].to_vec(), *src_size));
+        }
+        while pyramid.len() < levels {
+            let (previous, previous_size) = pyramid.last().unwrap();
+            if previous_size.w <= 1 && previous_size.h <= 1 {
+                break;
+            }
+            let size = Size {
+                w: previous_size.w.div_ceil(2),
+                h: previous_size.h.div_ceil(2),
+            };
+            let mut level = vec![0; size.expected_len(stride)];
+            box_resample(previous, previous_size, &mut level, &size, stride);
+            pyramid.push((level, size));
+        }
+        Self {
+            levels: pyramid,
+            stride,
+        }
+    }
+
+    /// The number of levels.
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns true if there are no levels.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// The per-pixel stride of every level.
+    pub const fn stride(&self) -> Stride {
+        self.stride
+    }
+
+    /// The pixels and size of level `index`, where 0 is full resolution.
+    pub fn level(&self, index: usize) -> Option<(&[u8], Size)> {
+        self.levels
+            .get(index)
+            .map(|(pixels, size)| (pixels.as_slice(), *size))
+    }
+
+    /// The index of the smallest level that is at least as large as the full-resolution image drawn at `scale`,
+    /// e.g. 1 for a scale of 0.5 and 0 for any scale of 1 or more.
+    pub fn level_for_scale(&self, scale: f32) -> usize {
+        if scale >= 1. || scale <= 0. || self.levels.is_empty() {
+            return 0;
+        }
+        ((1. / scale).log2().floor() as usize).min(self.levels.len() - 1)
+    }
+}
+
+/// Scale the region `world_rect` of the full-resolution image of `pyramid` to fill all of `dst`, an image of size `dst_size`,
+/// e.g. to pan and zoom over a very large image.
+///
+/// `world_rect` is in level-0 pixels and is clipped to the image. The smallest level with enough detail for the zoom is sampled,
+/// so the cost depends on the size of `dst` rather than on the size of `world_rect`.
+pub fn blit_lod(pyramid: &Pyramid, world_rect: &Rect, dst: &mut [u8], dst_size: &Size) {
+    let (Some((_, full_size)), false) = (pyramid.level(0), world_rect.is_empty()) else {
+        return;
+    };
+    let scale = (dst_size.w as f32 / world_rect.size.w as f32)
+        .max(dst_size.h as f32 / world_rect.size.h as f32);
+    let Some((level, level_size)) = pyramid.level(pyramid.level_for_scale(scale)) else {
+        return;
+    };
+    // Map the world rect to the level's coordinates.
+    let x0 = world_rect.position.x * level_size.w / full_size.w;
+    let y0 = world_rect.position.y * level_size.h / full_size.h;
+    let x1 = (world_rect.right() * level_size.w)
+        .div_ceil(full_size.w)
+        .min(level_size.w);
+    let y1 = (world_rect.bottom() * level_size.h)
+        .div_ceil(full_size.h)
+        .min(level_size.h);
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+    let rect = Rect::new(x0, y0, x1 - x0, y1 - y0);
+    let region = read_rows(level, &level_size, &rect, pyramid.stride)
+        .flatten()
+        .copied()
+        .collect::<Vec<u8>>();
+    box_resample(&region, &rect.size, dst, dst_size, pyramid.stride);
+}
+
+/// The location of one level of a mipmap chain generated by [`generate_mipmaps`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MipLevel {
+    /// The offset of the level in the chain's buffer, in bytes.
+    pub offset: usize,
+    /// The size of the level.
+    pub size: Size,
+}
+
+/// Generate a full mipmap chain from `src`, an image of size `src_size`, e.g. for software LOD selection or a GPU texture upload.
+///
+/// Returns one buffer with every level packed one after another, and the offset and size of each level.
+/// Level 0 is a copy of `src`. Each subsequent level is box-filtered to half the size of the previous one,
+/// rounded down but at least 1, which is how GPUs size mip levels. The last level is 1x1.
+pub fn generate_mipmaps(src: &[u8], src_size: &Size, stride: Stride) -> (Vec<u8>, Vec<MipLevel>) {
+    if src_size.w == 0 || src_size.h == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    let len = src_size.expected_len(stride);
+    let mut buffer = src[..len].to_vec();
+    let mut levels = vec![MipLevel {
+        offset: 0,
+        size: *src_size,
+    }];
+    loop {
+        let previous = *levels.last().unwrap();
+        if previous.size.w == 1 && previous.size.h == 1 {
+            break;
+        }
+        let level = MipLevel {
+            offset: buffer.len(),
+            size: Size {
+                w: (previous.size.w / 2).max(1),
+                h: (previous.size.h / 2).max(1),
+            },
+        };
+        buffer.resize(level.offset + level.size.expected_len(stride), 0);
+        let (head, tail) = buffer.split_at_mut(level.offset);
+        box_resample(
+            &head[previous.offset..],
+            &previous.size,
+            tail,
+            &level.size,
+            stride,
+        );
+        levels.push(level);
+    }
+    (buffer, levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_pyramid() {
+        let src = [0, 100, 200, 100, 0, 100, 200, 100, 0, 100, 200, 100];
+        let pyramid = Pyramid::build(&src, &Size { w: 4, h: 3 }, GRAYSCALE, 8);
+        assert_eq!(pyramid.len(), 3);
+        assert_eq!(pyramid.level(1).unwrap().1, Size { w: 2, h: 2 });
+        assert_eq!(pyramid.level(2).unwrap(), (&[100][..], Size { w: 1, h: 1 }));
+        assert_eq!(pyramid.level_for_scale(0.6), 0);
+        assert_eq!(pyramid.level_for_scale(0.5), 1);
+        assert_eq!(pyramid.level_for_scale(0.01), 2);
+
+        // The right half of the image, zoomed out to 1x1, samples level 1.
+        let mut dst = [0];
+        blit_lod(
+            &pyramid,
+            &Rect::new(2, 0, 2, 3),
+            &mut dst,
+            &Size { w: 1, h: 1 },
+        );
+        assert_eq!(dst, [pyramid.level(1).unwrap().0[1]]);
+    }
+
+    #[test]
+    fn test_generate_mipmaps() {
+        let src = [0, 100, 200, 100, 0, 100, 200, 100, 0, 100, 200, 100];
+        let (buffer, levels) = generate_mipmaps(&src, &Size { w: 4, h: 3 }, GRAYSCALE);
+        assert_eq!(
+            levels,
+            [
+                MipLevel {
+                    offset: 0,
+                    size: Size { w: 4, h: 3 }
+                },
+                MipLevel {
+                    offset: 12,
+                    size: Size { w: 2, h: 1 }
+                },
+                MipLevel {
+                    offset: 14,
+                    size: Size { w: 1, h: 1 }
+                }
+            ]
+        );
+        assert_eq!(buffer[..12], src);
+        assert_eq!(buffer.len(), 15);
+    }
+}
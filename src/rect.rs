@@ -0,0 +1,226 @@
+use crate::{PositionU, Size};
+
+/// A rectangle defined by its top-left position and its size.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub position: PositionU,
+    pub size: Size,
+}
+
+impl Rect {
+    /// Create a rectangle whose top-left corner is at `(x, y)`.
+    pub const fn new(x: usize, y: usize, w: usize, h: usize) -> Self {
+        Self {
+            position: PositionU { x, y },
+            size: Size { w, h },
+        }
+    }
+
+    /// The x coordinate one past the right edge.
+    pub const fn right(&self) -> usize {
+        self.position.x + self.size.w
+    }
+
+    /// The y coordinate one past the bottom edge.
+    pub const fn bottom(&self) -> usize {
+        self.position.y + self.size.h
+    }
+
+    /// Returns true if the rectangle has no area.
+    pub const fn is_empty(&self) -> bool {
+        self.size.w == 0 || self.size.h == 0
+    }
+
+    /// Convert a rectangle relative to `parent` into pixels.
+    ///
+    /// Edges are rounded to the nearest pixel, so adjacent relative rectangles share an edge without gaps or overlaps.
+    /// Values are clamped such that the result fits within `parent`.
+    pub fn from_relative(parent: &Size, rel: RelRect) -> Self {
+        let to_pixels = |fraction: f32, length: usize| {
+            (fraction.clamp(0., 1.) * length as f32).round() as usize
+        };
+        let x0 = to_pixels(rel.x, parent.w);
+        let y0 = to_pixels(rel.y, parent.h);
+        let x1 = to_pixels(rel.x + rel.w, parent.w).max(x0);
+        let y1 = to_pixels(rel.y + rel.h, parent.h).max(y0);
+        Self::new(x0, y0, x1 - x0, y1 - y0)
+    }
+
+    /// Shrink this rectangle by `insets` on each side, e.g. to apply padding or a margin.
+    ///
+    /// If the insets are larger than the rectangle, the result is empty.
+    pub const fn inset(&self, insets: &Insets) -> Self {
+        let w = self.size.w.saturating_sub(insets.left + insets.right);
+        let h = self.size.h.saturating_sub(insets.top + insets.bottom);
+        // Keep the position inside the original rectangle.
+        let x = self.position.x
+            + if insets.left < self.size.w {
+                insets.left
+            } else {
+                self.size.w
+            };
+        let y = self.position.y
+            + if insets.top < self.size.h {
+                insets.top
+            } else {
+                self.size.h
+            };
+        Self::new(x, y, w, h)
+    }
+
+    /// Split this rectangle into a left rectangle `x` pixels wide and a right rectangle with the remaining width.
+    ///
+    /// `x` is clamped to the width of this rectangle.
+    pub const fn split_h(&self, x: usize) -> (Self, Self) {
+        let x = if x < self.size.w { x } else { self.size.w };
+        (
+            Self::new(self.position.x, self.position.y, x, self.size.h),
+            Self::new(
+                self.position.x + x,
+                self.position.y,
+                self.size.w - x,
+                self.size.h,
+            ),
+        )
+    }
+
+    /// Split this rectangle into a top rectangle `y` pixels tall and a bottom rectangle with the remaining height.
+    ///
+    /// `y` is clamped to the height of this rectangle.
+    pub const fn split_v(&self, y: usize) -> (Self, Self) {
+        let y = if y < self.size.h { y } else { self.size.h };
+        (
+            Self::new(self.position.x, self.position.y, self.size.w, y),
+            Self::new(
+                self.position.x,
+                self.position.y + y,
+                self.size.w,
+                self.size.h - y,
+            ),
+        )
+    }
+
+    /// Iterate over every position inside this rectangle, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = PositionU> + use<> {
+        let x0 = self.position.x;
+        let x1 = self.right();
+        (self.position.y..self.bottom())
+            .flat_map(move |y| (x0..x1).map(move |x| PositionU { x, y }))
+    }
+
+    /// Divide this rectangle into tiles of `tile_size`, row by row.
+    ///
+    /// Tiles along the right and bottom edges are trimmed to fit inside this rectangle.
+    /// Yields nothing if either dimension of `tile_size` is zero.
+    pub fn tiles(&self, tile_size: Size) -> impl Iterator<Item = Rect> + use<> {
+        let rect = *self;
+        // A zero-sized tile produces empty ranges.
+        let empty = tile_size.w == 0 || tile_size.h == 0;
+        let (tile_w, tile_h) = (tile_size.w.max(1), tile_size.h.max(1));
+        let x1 = if empty { rect.position.x } else { rect.right() };
+        let y1 = if empty {
+            rect.position.y
+        } else {
+            rect.bottom()
+        };
+        (rect.position.y..y1).step_by(tile_h).flat_map(move |y| {
+            (rect.position.x..x1)
+                .step_by(tile_w)
+                .map(move |x| Rect::new(x, y, tile_w.min(x1 - x), tile_h.min(y1 - y)))
+        })
+    }
+}
+
+/// A rectangle whose position and size are fractions of a parent's size, e.g. `w: 0.5` is half of the parent's width.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RelRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+/// Distances in pixels from each side of a rectangle.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Insets {
+    pub left: usize,
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+}
+
+impl Insets {
+    /// The same distance on every side.
+    pub const fn uniform(value: usize) -> Self {
+        Self {
+            left: value,
+            top: value,
+            right: value,
+            bottom: value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions() {
+        let rect = Rect::new(2, 3, 4, 2);
+        let positions = rect.positions().collect::<Vec<PositionU>>();
+        assert_eq!(positions.len(), 8);
+        assert_eq!(positions[0], PositionU { x: 2, y: 3 });
+        assert_eq!(positions[7], PositionU { x: 5, y: 4 });
+        assert_eq!(Rect::new(2, 3, 0, 2).positions().count(), 0);
+    }
+
+    #[test]
+    fn test_tiles() {
+        let rect = Rect::new(1, 1, 10, 5);
+        let tiles = rect.tiles(Size { w: 4, h: 4 }).collect::<Vec<Rect>>();
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[0], Rect::new(1, 1, 4, 4));
+        assert_eq!(tiles[2], Rect::new(9, 1, 2, 4));
+        assert_eq!(tiles[5], Rect::new(9, 5, 2, 1));
+        let area: usize = tiles.iter().map(|tile| tile.size.w * tile.size.h).sum();
+        assert_eq!(area, 50);
+        assert_eq!(rect.tiles(Size { w: 0, h: 4 }).count(), 0);
+    }
+
+    #[test]
+    fn test_layout() {
+        let parent = Size { w: 101, h: 50 };
+        let left = Rect::from_relative(
+            &parent,
+            RelRect {
+                x: 0.,
+                y: 0.,
+                w: 0.5,
+                h: 1.,
+            },
+        );
+        let right = Rect::from_relative(
+            &parent,
+            RelRect {
+                x: 0.5,
+                y: 0.,
+                w: 0.5,
+                h: 1.,
+            },
+        );
+        assert_eq!(left.right(), right.position.x);
+        assert_eq!(left.size.w + right.size.w, parent.w);
+
+        let rect = Rect::new(0, 0, 20, 10).inset(&Insets::uniform(2));
+        assert_eq!(rect, Rect::new(2, 2, 16, 6));
+        assert!(rect.inset(&Insets::uniform(10)).is_empty());
+
+        let (top, bottom) = rect.split_v(4);
+        assert_eq!(top, Rect::new(2, 2, 16, 4));
+        assert_eq!(bottom, Rect::new(2, 6, 16, 2));
+        let (left, right) = rect.split_h(100);
+        assert_eq!(left, rect);
+        assert!(right.is_empty());
+    }
+}
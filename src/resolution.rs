@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::{FitMode, Rect, Size, present_fit, stride::Stride};
+
+/// Renders into an internal image whose resolution follows recent frame times, to hold a target frame rate.
+///
+/// Each frame, render into [`DynamicResolution::image_mut`], present with [`DynamicResolution::present`],
+/// and then report the frame's duration with [`DynamicResolution::update`].
+///
+/// Frame times are smoothed, and the resolution only changes after `patience` consecutive frames outside of the `hysteresis` band,
+/// so it doesn't oscillate between two sizes.
+pub struct DynamicResolution {
+    /// The target duration of each frame.
+    pub target: Duration,
+    /// The smallest allowed scale, relative to the full size.
+    pub min_scale: f32,
+    /// The largest allowed scale, relative to the full size.
+    pub max_scale: f32,
+    /// How much the scale changes at a time.
+    pub step: f32,
+    /// How far, as a fraction of `target`, the smoothed frame time must be from `target` to change the scale.
+    pub hysteresis: f32,
+    /// The number of consecutive frames that must be too slow (or too fast) to change the scale.
+    pub patience: u32,
+    full_size: Size,
+    stride: Stride,
+    scale: f32,
+    size: Size,
+    image: Vec<u8>,
+    average: Option<Duration>,
+    streak: i32,
+}
+
+impl DynamicResolution {
+    /// Create a helper that renders at up to `full_size` and targets frames of `target` duration. It starts at full scale.
+    pub fn new(full_size: Size, stride: Stride, target: Duration) -> Self {
+        Self {
+            target,
+            min_scale: 0.5,
+            max_scale: 1.,
+            step: 0.1,
+            hysteresis: 0.1,
+            patience: 10,
+            full_size,
+            stride,
+            scale: 1.,
+            size: full_size,
+            image: vec![0; full_size.w * full_size.h * stride],
+            average: None,
+            streak: 0,
+        }
+    }
+
+    /// The current scale, relative to the full size.
+    pub const fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// The current size of the internal image.
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The internal image, to render into. Its size is [`DynamicResolution::size`].
+    pub fn image_mut(&mut self) -> &mut [u8] {
+        &mut self.image
+    }
+
+    /// Scale the internal image to fill `dst`. Returns the area of `dst` that was drawn to.
+    pub fn present(&self, dst: &mut [u8], dst_size: &Size) -> Rect {
+        // Stretching never leaves bars, so the bar color is never drawn.
+        present_fit(
+            &self.image,
+            &self.size,
+            dst,
+            dst_size,
+            self.stride,
+            FitMode::Stretch,
+            &vec![0; self.stride.get()],
+        )
+    }
+
+    /// Report the duration of the frame that just ended. Returns true if the internal image was resized.
+    ///
+    /// After a resize, the internal image is zeroed.
+    pub fn update(&mut self, frame: Duration) -> bool {
+        // An exponential moving average of the frame time.
+        let average = match self.average {
+            Some(average) => average.mul_f32(0.8) + frame.mul_f32(0.2),
+            None => frame,
+        };
+        self.average = Some(average);
+        let ratio = average.as_secs_f32() / self.target.as_secs_f32();
+        if ratio > 1. + self.hysteresis {
+            self.streak = self.streak.min(0) - 1;
+        } else if ratio < 1. - self.hysteresis {
+            self.streak = self.streak.max(0) + 1;
+        } else {
+            self.streak = 0;
+        }
+        let patience = self.patience.max(1) as i32;
+        let scale = if self.streak <= -patience {
+            self.scale - self.step
+        } else if self.streak >= patience {
+            self.scale + self.step
+        } else {
+            return false;
+        };
+        self.streak = 0;
+        self.set_scale(scale)
+    }
+
+    /// Set the scale, clamped to `min_scale` and `max_scale`. Returns true if the internal image was resized.
+    pub fn set_scale(&mut self, scale: f32) -> bool {
+        self.scale = scale.clamp(self.min_scale, self.max_scale);
+        let size = Size {
+            w: ((self.full_size.w as f32 * self.scale).round() as usize).max(1),
+            h: ((self.full_size.h as f32 * self.scale).round() as usize).max(1),
+        };
+        if size == self.size {
+            false
+        } else {
+            self.size = size;
+            self.image.clear();
+            self.image.resize(size.w * size.h * self.stride, 0);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_dynamic_resolution() {
+        let target = Duration::from_millis(10);
+        let mut resolution = DynamicResolution::new(Size { w: 100, h: 50 }, GRAYSCALE, target);
+        resolution.patience = 3;
+        // Frames on target don't change the resolution.
+        (0..5).for_each(|_| assert!(!resolution.update(target)));
+        assert_eq!(resolution.size(), Size { w: 100, h: 50 });
+        // Consistently slow frames lower it.
+        assert!(!resolution.update(target * 3));
+        assert!(!resolution.update(target * 3));
+        assert!(resolution.update(target * 3));
+        assert_eq!(resolution.size(), Size { w: 90, h: 45 });
+        assert_eq!(resolution.image_mut().len(), 90 * 45);
+
+        let mut dst = vec![0; 100 * 50];
+        let rect = resolution.present(&mut dst, &Size { w: 100, h: 50 });
+        assert_eq!(rect, Rect::new(0, 0, 100, 50));
+    }
+}
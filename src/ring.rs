@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Size, stride::Stride};
+
+/// A fixed number of pre-allocated frames that are passed from a producer (e.g. a capture or decode thread) to a consumer (e.g. the compositor) without allocating or copying.
+///
+/// The producer takes a frame with [`FrameRing::acquire_write`], fills it, and hands it back with [`FrameRing::commit`].
+/// The consumer takes the oldest committed frame with [`FrameRing::acquire_read`], reads it, and hands it back with [`FrameRing::release`].
+/// If the consumer falls behind and there's no free frame, acquiring a frame to write drops the oldest unread frame.
+///
+/// The frames are owned `Vec`s that move in and out of the ring, so the ring's internal lock is only held while a frame is taken or returned,
+/// never while it's being written or read. To share a ring between threads, wrap it in an `Arc`.
+pub struct FrameRing {
+    state: Mutex<State>,
+    size: Size,
+    stride: Stride,
+}
+
+struct State {
+    /// Frames that aren't being written, read, or waiting to be read.
+    free: Vec<Vec<u8>>,
+    /// Committed, unread frames, oldest first.
+    ready: VecDeque<Vec<u8>>,
+    dropped: u64,
+}
+
+impl FrameRing {
+    /// Allocate `count` frames of size `size`. Panics if `count` is less than 3, which is the minimum for the producer and consumer to never block each other.
+    pub fn new(count: usize, size: Size, stride: Stride) -> Self {
+        assert!(count >= 3, "A frame ring needs at least 3 frames");
+        Self {
+            state: Mutex::new(State {
+                free: (0..count)
+                    .map(|_| vec![0; size.expected_len(stride)])
+                    .collect(),
+                ready: VecDeque::with_capacity(count),
+                dropped: 0,
+            }),
+            size,
+            stride,
+        }
+    }
+
+    /// The size of every frame.
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The per-pixel stride of every frame.
+    pub const fn stride(&self) -> Stride {
+        self.stride
+    }
+
+    /// Take a frame for the producer to write. Its previous contents are undefined.
+    ///
+    /// Pass it back to [`FrameRing::commit`] when it's written.
+    /// Panics if the producer and consumer are holding every frame, i.e. if frames are acquired without being committed or released.
+    pub fn acquire_write(&self) -> Vec<u8> {
+        let mut state = self.lock();
+        match state.free.pop() {
+            Some(frame) => frame,
+            None => {
+                // Every other frame is either unread or being read, so drop the oldest unread frame.
+                state.dropped += 1;
+                state
+                    .ready
+                    .pop_front()
+                    .expect("Every frame of the ring has been acquired")
+            }
+        }
+    }
+
+    /// Publish a frame returned by [`FrameRing::acquire_write`].
+    ///
+    /// Panics if the frame isn't the ring's frame length.
+    pub fn commit(&self, frame: Vec<u8>) {
+        self.assert_frame_len(&frame);
+        self.lock().ready.push_back(frame);
+    }
+
+    /// Take the oldest committed frame for the consumer to read, or `None` if there isn't one.
+    ///
+    /// Pass it back to [`FrameRing::release`] when it's read so that the producer can reuse it.
+    pub fn acquire_read(&self) -> Option<Vec<u8>> {
+        self.lock().ready.pop_front()
+    }
+
+    /// Return a frame returned by [`FrameRing::acquire_read`] so that the producer can reuse it.
+    ///
+    /// Panics if the frame isn't the ring's frame length.
+    pub fn release(&self, frame: Vec<u8>) {
+        self.assert_frame_len(&frame);
+        self.lock().free.push(frame);
+    }
+
+    /// The number of committed frames waiting to be read.
+    pub fn ready_len(&self) -> usize {
+        self.lock().ready.len()
+    }
+
+    /// The number of frames that were dropped because the consumer fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+
+    /// Panics if `frame` isn't the ring's frame length, e.g. if it was resized, so that it's never handed out again.
+    fn assert_frame_len(&self, frame: &[u8]) {
+        let len = self.size.expected_len(self.stride);
+        assert_eq!(
+            frame.len(),
+            len,
+            "The frame is {} bytes long but the ring's frames are {len} bytes long",
+            frame.len()
+        );
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_frame_ring() {
+        let ring = FrameRing::new(3, Size { w: 1, h: 1 }, GRAYSCALE);
+        assert!(ring.acquire_read().is_none());
+        (1..=4).for_each(|value| {
+            let mut frame = ring.acquire_write();
+            frame[0] = value;
+            ring.commit(frame);
+        });
+        // There are only three frames, so frame 1 was dropped to write frame 4.
+        assert_eq!(ring.dropped(), 1);
+        assert_eq!(ring.ready_len(), 3);
+        let reading = ring.acquire_read().unwrap();
+        assert_eq!(reading, [2]);
+        // The frame being read isn't reused, so frame 3 is dropped instead.
+        let mut frame = ring.acquire_write();
+        frame[0] = 5;
+        ring.commit(frame);
+        assert_eq!(ring.dropped(), 2);
+        assert_eq!(reading, [2]);
+        ring.release(reading);
+        let frame = ring.acquire_read().unwrap();
+        assert_eq!(frame, [4]);
+        ring.release(frame);
+        let frame = ring.acquire_read().unwrap();
+        assert_eq!(frame, [5]);
+        ring.release(frame);
+        assert!(ring.acquire_read().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_frame_ring_len() {
+        let ring = FrameRing::new(3, Size { w: 2, h: 2 }, GRAYSCALE);
+        let mut frame = ring.acquire_write();
+        frame.pop();
+        ring.commit(frame);
+    }
+
+    #[test]
+    fn test_frame_ring_threads() {
+        const FRAMES: u8 = 200;
+        let ring = FrameRing::new(3, Size { w: 1, h: 1 }, GRAYSCALE);
+        let received = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                (1..=FRAMES).for_each(|value| {
+                    let mut frame = ring.acquire_write();
+                    frame[0] = value;
+                    ring.commit(frame);
+                });
+            });
+            let mut received = Vec::new();
+            while received.last() != Some(&FRAMES) {
+                if let Some(frame) = ring.acquire_read() {
+                    received.push(frame[0]);
+                    ring.release(frame);
+                }
+            }
+            received
+        });
+        // Frames are received in order, and every frame is either received or dropped.
+        assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(received.len() as u64 + ring.dropped(), u64::from(FRAMES));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// A boolean raster operation that combines each bit of a source pixel with the destination.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Rop {
+    /// `src`
+    #[default]
+    Copy,
+    /// `!src`
+    NotCopy,
+    /// `src & dst`
+    And,
+    /// `src | dst`
+    Or,
+    /// `src ^ dst`, e.g. for drawing a cursor that is erased by drawing it again.
+    Xor,
+    /// `!dst`. The source is ignored, but it still defines the affected area.
+    Invert,
+    /// `src & !dst`
+    SrcAndNotDst,
+    /// `!src & dst`, e.g. for punching a mask out of the destination.
+    NotSrcAndDst,
+    /// `!(src | dst)`
+    Nor,
+    /// `!src | dst`
+    NotSrcOrDst,
+}
+
+impl Rop {
+    /// Apply the operation to each bit of `src` and `dst`.
+    pub const fn apply(&self, src: u8, dst: u8) -> u8 {
+        match self {
+            Self::Copy => src,
+            Self::NotCopy => !src,
+            Self::And => src & dst,
+            Self::Or => src | dst,
+            Self::Xor => src ^ dst,
+            Self::Invert => !dst,
+            Self::SrcAndNotDst => src & !dst,
+            Self::NotSrcAndDst => !src & dst,
+            Self::Nor => !(src | dst),
+            Self::NotSrcOrDst => !src | dst,
+        }
+    }
+}
+
+/// Blit `src` onto `dst`, combining each byte with the destination using `rop`.
+///
+/// This is the same as [`crate::blit`] except for the raster operation. Every byte is combined, so this works for 8bpp buffers and any other stride.
+pub fn blit_rop(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    rop: Rop,
+) {
+    if src_size.w > 0 && src_size.h > 0 {
+        let src_w_stride = src_size.w * stride;
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, stride);
+            let dst_index = get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+            dst[dst_index..dst_index + src_w_stride]
+                .iter_mut()
+                .zip(&src[src_index..src_index + src_w_stride])
+                .for_each(|(d, s)| *d = rop.apply(*s, *d));
+        });
+    }
+}
+
+/// Blit the 1bpp image `src` onto the 1bpp image `dst`, combining each bit with the destination using `rop`.
+///
+/// Pixels are packed eight to a byte, most significant bit first, and each row starts on a new byte.
+/// `dst_position.x` doesn't need to be a multiple of 8.
+pub fn blit_rop_1bpp(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    rop: Rop,
+) {
+    let src_row_len = src_size.w.div_ceil(8);
+    let dst_row_len = dst_size.w.div_ceil(8);
+    (0..src_size.h).for_each(|src_y| {
+        let src_row = &src[src_y * src_row_len..(src_y + 1) * src_row_len];
+        let dst_y = dst_position.y + src_y;
+        let dst_row = &mut dst[dst_y * dst_row_len..(dst_y + 1) * dst_row_len];
+        if dst_position.x.is_multiple_of(8) {
+            // The bits line up, so combine whole bytes and mask the last one.
+            let dst_row = &mut dst_row[dst_position.x / 8..];
+            src_row.iter().enumerate().for_each(|(i, s)| {
+                let bits = (src_size.w - i * 8).min(8);
+                let mask = !(0xFFu8.checked_shr(bits as u32).unwrap_or(0));
+                let d = &mut dst_row[i];
+                *d = (*d & !mask) | (rop.apply(*s, *d) & mask);
+            });
+        } else {
+            (0..src_size.w).for_each(|src_x| {
+                let s = (src_row[src_x / 8] >> (7 - src_x % 8)) & 1;
+                let dst_x = dst_position.x + src_x;
+                let shift = 7 - dst_x % 8;
+                let d = &mut dst_row[dst_x / 8];
+                let bit = rop.apply(s, (*d >> shift) & 1) & 1;
+                *d = (*d & !(1 << shift)) | (bit << shift);
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_rop() {
+        let mut dst = [0b1100, 0b1010];
+        blit_rop(
+            &[0b1010],
+            &Size { w: 1, h: 1 },
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &Size { w: 2, h: 1 },
+            GRAYSCALE,
+            Rop::Xor,
+        );
+        assert_eq!(dst, [0b1100, 0]);
+
+        // Two 3-pixel rows onto an 8x2 image, aligned and unaligned.
+        let src = [0b1010_0000, 0b0110_0000];
+        let src_size = Size { w: 3, h: 2 };
+        let dst_size = Size { w: 8, h: 2 };
+        let mut dst = [0b1111_1111, 0];
+        blit_rop_1bpp(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &dst_size,
+            Rop::And,
+        );
+        assert_eq!(dst, [0b1011_1111, 0]);
+        blit_rop_1bpp(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU { x: 3, y: 0 },
+            &dst_size,
+            Rop::NotCopy,
+        );
+        assert_eq!(dst, [0b1010_1011, 0b0001_0000]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{PositionU, Size, get_index, stride::Stride, transpose::for_each_blocked};
+
+/// A clockwise rotation by a multiple of 90 degrees.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    R90,
+    R180,
+    R270,
+}
+
+impl Rotation {
+    /// The size of an image of size `size` after it's rotated.
+    pub const fn rotated_size(&self, size: &Size) -> Size {
+        match self {
+            Self::R180 => *size,
+            Self::R90 | Self::R270 => Size {
+                w: size.h,
+                h: size.w,
+            },
+        }
+    }
+}
+
+/// Blit `src` onto `dst`, rotated clockwise by `rotation`.
+///
+/// `dst_position` is the top-left position of the rotated image, whose size is [`Rotation::rotated_size`]. It must fit within `dst`.
+/// 90 and 270 degree rotations are done in small square blocks so that reads and writes both stay in the cache, even for large images.
+pub fn blit_rotated(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    rotation: Rotation,
+) {
+    let (w, h) = (src_size.w, src_size.h);
+    let mut copy = |src_x: usize, src_y: usize, dst_x: usize, dst_y: usize| {
+        let src_index = get_index(src_x, src_y, w, stride);
+        let dst_index = get_index(
+            dst_position.x + dst_x,
+            dst_position.y + dst_y,
+            dst_size.w,
+            stride,
+        );
+        dst[dst_index..dst_index + stride.get()]
+            .copy_from_slice(&src[src_index..src_index + stride.get()]);
+    };
+    match rotation {
+        Rotation::R180 => (0..h).for_each(|y| {
+            (0..w).for_each(|x| copy(x, y, w - 1 - x, h - 1 - y));
+        }),
+        Rotation::R90 => for_each_blocked(src_size, |x, y| copy(x, y, h - 1 - y, x)),
+        Rotation::R270 => for_each_blocked(src_size, |x, y| copy(x, y, y, w - 1 - x)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_rotated() {
+        // 1 2 3
+        // 4 5 6
+        let src = [1, 2, 3, 4, 5, 6];
+        let size = Size { w: 3, h: 2 };
+        let rotate = |rotation: Rotation| {
+            let dst_size = rotation.rotated_size(&size);
+            let mut dst = vec![0; 6];
+            blit_rotated(
+                &src,
+                &size,
+                &mut dst,
+                &PositionU::default(),
+                &dst_size,
+                GRAYSCALE,
+                rotation,
+            );
+            dst
+        };
+        assert_eq!(rotate(Rotation::R90), [4, 1, 5, 2, 6, 3]);
+        assert_eq!(rotate(Rotation::R180), [6, 5, 4, 3, 2, 1]);
+        assert_eq!(rotate(Rotation::R270), [3, 6, 2, 5, 1, 4]);
+
+        // Larger than a block.
+        let size = Size { w: 40, h: 20 };
+        let src = (0..800).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        let mut rotated = vec![0; 800];
+        let mut restored = vec![0; 800];
+        blit_rotated(
+            &src,
+            &size,
+            &mut rotated,
+            &PositionU::default(),
+            &Rotation::R90.rotated_size(&size),
+            GRAYSCALE,
+            Rotation::R90,
+        );
+        blit_rotated(
+            &rotated,
+            &Rotation::R90.rotated_size(&size),
+            &mut restored,
+            &PositionU::default(),
+            &size,
+            GRAYSCALE,
+            Rotation::R270,
+        );
+        assert_eq!(restored, src);
+    }
+}
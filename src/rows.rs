@@ -0,0 +1,100 @@
+use crate::{PositionU, Rect, Size, get_index, stride::Stride};
+
+/// Blit rows of pixels onto `dst`, for sources that aren't contiguous in memory such as ring buffers, decoded chunks, or network packets.
+///
+/// - `rows` yields the source image's rows from top to bottom. Only the first `src_w * stride` bytes of each row are copied.
+/// - `src_w` is the width of the source image in pixels.
+/// - `dst_position` is the top-left position of the region that the rows will blit onto.
+///
+/// Rows that would be below the bottom of `dst` aren't consumed.
+/// Returns the number of rows that were blitted.
+pub fn blit_from_rows<'a, I>(
+    rows: I,
+    src_w: usize,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+) -> usize
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let src_w_stride = src_w * stride;
+    let max_rows = dst_size.h.saturating_sub(dst_position.y);
+    let mut count = 0;
+    if src_w > 0 {
+        rows.into_iter()
+            .take(max_rows)
+            .enumerate()
+            .for_each(|(src_y, row)| {
+                let dst_index =
+                    get_index(dst_position.x, dst_position.y + src_y, dst_size.w, stride);
+                dst[dst_index..dst_index + src_w_stride].copy_from_slice(&row[..src_w_stride]);
+                count += 1;
+            });
+    }
+    count
+}
+
+/// Iterate over the rows of `rect` within `src`, top to bottom, without copying them, e.g. to stream a region to an encoder.
+///
+/// - `src` is a flat byte slice of an image of size `src_size`.
+///
+/// `rect` is clipped to `src_size`. Each yielded row is `w * stride` bytes long, where `w` is the clipped width.
+pub fn read_rows<'a>(
+    src: &'a [u8],
+    src_size: &Size,
+    rect: &Rect,
+    stride: Stride,
+) -> impl Iterator<Item = &'a [u8]> + use<'a> {
+    let x0 = rect.position.x.min(src_size.w);
+    let x1 = rect.right().min(src_size.w);
+    let y0 = rect.position.y.min(src_size.h);
+    let y1 = rect.bottom().min(src_size.h);
+    let src_w = src_size.w;
+    let len = (x1 - x0) * stride;
+    (y0..y1).map(move |y| {
+        let index = get_index(x0, y, src_w, stride);
+        &src[index..index + len]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGB};
+
+    #[test]
+    fn test_blit_from_rows() {
+        // Two rows that aren't contiguous, with trailing padding.
+        let row_0 = [1u8; 3 * RGB.get() + 2];
+        let row_1 = [2u8; 3 * RGB.get()];
+        let dst_size = Size { w: 4, h: 2 };
+        let mut dst = [0u8; 4 * 2 * RGB.get()];
+        let count = blit_from_rows(
+            [&row_0[..], &row_1[..], &row_1[..]],
+            3,
+            &mut dst,
+            &PositionU { x: 1, y: 0 },
+            &dst_size,
+            RGB,
+        );
+        assert_eq!(count, 2);
+        assert_eq!(dst[..RGB.get()], [0; RGB.get()]);
+        assert_eq!(dst[RGB.get()..4 * RGB], [1; 3 * RGB.get()]);
+        assert_eq!(dst[5 * RGB..], [2; 3 * RGB.get()]);
+    }
+
+    #[test]
+    fn test_read_rows() {
+        let src = (0..4 * 3).collect::<Vec<u8>>();
+        let src_size = Size { w: 4, h: 3 };
+        let rows =
+            read_rows(&src, &src_size, &Rect::new(2, 1, 5, 5), GRAYSCALE).collect::<Vec<&[u8]>>();
+        assert_eq!(rows, [&[6, 7], &[10, 11]]);
+        assert_eq!(
+            read_rows(&src, &src_size, &Rect::new(9, 9, 1, 1), GRAYSCALE).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{PositionU, Rect, Size, get_index, stride::Stride};
+
+/// Resample all of `src` to fill all of `dst` by averaging the source pixels that each destination pixel covers (a box filter).
+///
+/// When upscaling, each destination pixel covers less than one source pixel, so this is equivalent to nearest-neighbor sampling.
+pub(crate) fn box_resample(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_size: &Size,
+    stride: Stride,
+) {
+    blit_scaled_box(
+        src,
+        src_size,
+        dst,
+        &Rect::new(0, 0, dst_size.w, dst_size.h),
+        dst_size,
+        stride,
+    );
+}
+
+/// How [`blit_scaled_box`] and [`blit_scaled_bilinear`] read, filter and write pixels.
+///
+/// A [`Stride`] filters every byte of a pixel independently, so it works for any 8-bit-per-channel format.
+/// A [`crate::PixelType`] filters whole [`crate::Pixel`]s, for custom formats whose channels aren't bytes.
+pub trait Resample: Copy {
+    /// The number of bytes per pixel.
+    fn stride(&self) -> Stride;
+
+    /// Write the average of `pixels` to `dst`. There's always at least one pixel.
+    fn average<'a>(&self, pixels: impl Iterator<Item = &'a [u8]> + Clone, dst: &mut [u8]);
+
+    /// Write the bilinear mix of four pixels to `dst`.
+    /// `weight_x` and `weight_y` are the weights in `[0, 256]` of the right and bottom pixels.
+    fn bilinear(
+        &self,
+        top: [&[u8]; 2],
+        bottom: [&[u8]; 2],
+        weight_x: u32,
+        weight_y: u32,
+        dst: &mut [u8],
+    );
+}
+
+impl Resample for Stride {
+    fn stride(&self) -> Stride {
+        *self
+    }
+
+    fn average<'a>(&self, pixels: impl Iterator<Item = &'a [u8]> + Clone, dst: &mut [u8]) {
+        let count = pixels.clone().count() as u32;
+        dst[..self.get()].iter_mut().enumerate().for_each(|(i, c)| {
+            let sum = pixels.clone().map(|pixel| pixel[i] as u32).sum::<u32>();
+            *c = ((sum + count / 2) / count) as u8;
+        });
+    }
+
+    fn bilinear(
+        &self,
+        top: [&[u8]; 2],
+        bottom: [&[u8]; 2],
+        weight_x: u32,
+        weight_y: u32,
+        dst: &mut [u8],
+    ) {
+        dst[..self.get()].iter_mut().enumerate().for_each(|(i, c)| {
+            let mix = |[left, right]: [&[u8]; 2]| {
+                left[i] as u32 * (256 - weight_x) + right[i] as u32 * weight_x
+            };
+            let value = mix(top) * (256 - weight_y) + mix(bottom) * weight_y;
+            *c = ((value + (1 << 15)) >> 16) as u8;
+        });
+    }
+}
+
+/// Scale all of `src` to fill `dst_rect` by averaging the source pixels that each destination pixel covers (a box filter),
+/// e.g. to draw a minimap or thumbnail of a framebuffer without the aliasing of nearest-neighbor sampling.
+///
+/// `format` is usually the per-pixel stride. See [`Resample`].
+/// `dst_rect` is clipped to `dst_size`.
+/// When upscaling, each destination pixel covers less than one source pixel, so this is equivalent to nearest-neighbor sampling.
+pub fn blit_scaled_box<R: Resample>(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_rect: &Rect,
+    dst_size: &Size,
+    format: R,
+) {
+    if src_size.w == 0 || src_size.h == 0 || dst_rect.is_empty() {
+        return;
+    }
+    let x1 = dst_rect.right().min(dst_size.w);
+    let y1 = dst_rect.bottom().min(dst_size.h);
+    if x1 <= dst_rect.position.x || y1 <= dst_rect.position.y {
+        return;
+    }
+    let stride = format.stride();
+    // The source column range covered by each visible destination column.
+    let x_ranges = get_ranges(src_size.w, dst_rect.size.w, x1 - dst_rect.position.x);
+    (0..y1 - dst_rect.position.y).for_each(|y| {
+        let (y0, y1) = get_range(y, src_size.h, dst_rect.size.h);
+        x_ranges.iter().enumerate().for_each(|(x, (x0, x1))| {
+            let pixels = (y0..y1).flat_map(|src_y| {
+                let index = get_index(*x0, src_y, src_size.w, stride);
+                src[index..index + (x1 - x0) * stride].chunks_exact(stride.get())
+            });
+            let index = get_index(
+                dst_rect.position.x + x,
+                dst_rect.position.y + y,
+                dst_size.w,
+                stride,
+            );
+            format.average(pixels, &mut dst[index..index + stride.get()]);
+        });
+    });
+}
+
+/// Scale all of `src` to fill `dst_rect` with bilinear filtering, e.g. for UI images that look too blocky when nearest-neighbor sampled.
+///
+/// `format` is usually the per-pixel stride. See [`Resample`].
+/// `dst_rect` is clipped to `dst_size`. Sampling positions and weights are fixed-point, so with a stride the inner loop is integer math.
+pub fn blit_scaled_bilinear<R: Resample>(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_rect: &Rect,
+    dst_size: &Size,
+    format: R,
+) {
+    if src_size.w == 0 || src_size.h == 0 || dst_rect.is_empty() {
+        return;
+    }
+    let x1 = dst_rect.right().min(dst_size.w);
+    let y1 = dst_rect.bottom().min(dst_size.h);
+    if x1 <= dst_rect.position.x || y1 <= dst_rect.position.y {
+        return;
+    }
+    let stride = format.stride();
+    // The left source pixel and the weight of the right source pixel for each destination column.
+    let columns = (0..x1 - dst_rect.position.x)
+        .map(|x| {
+            let (x0, weight) = get_sample(x, src_size.w, dst_rect.size.w);
+            (x0 * stride, (x0 + 1).min(src_size.w - 1) * stride, weight)
+        })
+        .collect::<Vec<(usize, usize, u32)>>();
+    let src_row_len = src_size.w * stride;
+    (0..y1 - dst_rect.position.y).for_each(|y| {
+        let (y0, weight_y) = get_sample(y, src_size.h, dst_rect.size.h);
+        let top = &src[y0 * src_row_len..(y0 + 1) * src_row_len];
+        let y1 = (y0 + 1).min(src_size.h - 1);
+        let bottom = &src[y1 * src_row_len..(y1 + 1) * src_row_len];
+        let dst_index = get_index(
+            dst_rect.position.x,
+            dst_rect.position.y + y,
+            dst_size.w,
+            stride,
+        );
+        dst[dst_index..dst_index + columns.len() * stride]
+            .chunks_exact_mut(stride.get())
+            .zip(&columns)
+            .for_each(|(pixel, (left, right, weight_x))| {
+                let (left, right) = (*left..*left + stride.get(), *right..*right + stride.get());
+                format.bilinear(
+                    [&top[left.clone()], &top[right.clone()]],
+                    [&bottom[left], &bottom[right]],
+                    *weight_x,
+                    weight_y,
+                    pixel,
+                );
+            });
+    });
+}
+
+/// Blit `src` onto `dst` scaled up by the integer `factor`, duplicating each pixel into a `factor` × `factor` block, e.g. to scale pixel art to the window.
+///
+/// `dst_position` is the top-left position of the scaled image, which is `src_size.w * factor` by `src_size.h * factor` pixels and must fit within `dst`.
+/// Each scaled row is built once and then copied `factor` times, so this is much faster than a general scaling blit.
+/// A `factor` of 0 blits nothing.
+pub fn blit_upscale_n(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    factor: usize,
+) {
+    if factor == 0 || src_size.w == 0 || src_size.h == 0 {
+        return;
+    }
+    let src_row_len = src_size.w * stride;
+    let dst_row_len = src_row_len * factor;
+    (0..src_size.h).for_each(|src_y| {
+        let src_row = &src[src_y * src_row_len..(src_y + 1) * src_row_len];
+        let dst_y = dst_position.y + src_y * factor;
+        let first = get_index(dst_position.x, dst_y, dst_size.w, stride);
+        dst[first..first + dst_row_len]
+            .chunks_exact_mut(stride.get() * factor)
+            .zip(src_row.chunks_exact(stride.get()))
+            .for_each(|(block, pixel)| {
+                block
+                    .chunks_exact_mut(stride.get())
+                    .for_each(|d| d.copy_from_slice(pixel))
+            });
+        (1..factor).for_each(|i| {
+            let index = get_index(dst_position.x, dst_y + i, dst_size.w, stride);
+            dst.copy_within(first..first + dst_row_len, index);
+        });
+    });
+}
+
+/// Returns the source index to the left of the center of destination index `i`,
+/// and the weight in `[0, 256]` of the next source index.
+fn get_sample(i: usize, src_len: usize, dst_len: usize) -> (usize, u32) {
+    // 16.16 fixed point: (i + 0.5) * src_len / dst_len - 0.5
+    let position = (((2 * i + 1) * src_len) << 16) / (2 * dst_len);
+    let position = position.saturating_sub(1 << 15);
+    let index = position >> 16;
+    if index >= src_len - 1 {
+        (src_len - 1, 0)
+    } else {
+        (index, ((position & 0xFFFF) >> 8) as u32)
+    }
+}
+
+/// Returns the range of source indices covered by each of the first `count` destination indices.
+fn get_ranges(src_len: usize, dst_len: usize, count: usize) -> Vec<(usize, usize)> {
+    (0..count).map(|i| get_range(i, src_len, dst_len)).collect()
+}
+
+/// Returns the range of source indices covered by destination index `i`. The range always contains at least one index.
+const fn get_range(i: usize, src_len: usize, dst_len: usize) -> (usize, usize) {
+    let start = i * src_len / dst_len;
+    let end = (i + 1) * src_len / dst_len;
+    (start, if end > start { end } else { start + 1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_scaled_bilinear() {
+        // Upscale a 2x1 gradient into the middle of a 6x2 image.
+        let src = [0, 200];
+        let mut dst = [9; 12];
+        let dst_size = Size { w: 6, h: 2 };
+        blit_scaled_bilinear(
+            &src,
+            &Size { w: 2, h: 1 },
+            &mut dst,
+            &Rect::new(1, 1, 4, 1),
+            &dst_size,
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [9, 9, 9, 9, 9, 9, 9, 0, 50, 150, 200, 9]);
+
+        // A rect that is partially outside of `dst` is clipped.
+        let mut dst = [9; 12];
+        blit_scaled_bilinear(
+            &src,
+            &Size { w: 2, h: 1 },
+            &mut dst,
+            &Rect::new(4, 1, 4, 2),
+            &dst_size,
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 0, 50]);
+    }
+
+    #[test]
+    fn test_blit_scaled_box() {
+        let src = [0, 10, 20, 30, 40, 50, 60, 70];
+        let mut dst = [9; 6];
+        blit_scaled_box(
+            &src,
+            &Size { w: 4, h: 2 },
+            &mut dst,
+            &Rect::new(1, 0, 2, 1),
+            &Size { w: 3, h: 2 },
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [9, 25, 45, 9, 9, 9]);
+    }
+
+    #[test]
+    fn test_blit_upscale_n() {
+        let mut dst = [0; 5 * 4];
+        blit_upscale_n(
+            &[1, 2],
+            &Size { w: 2, h: 1 },
+            &mut dst,
+            &PositionU { x: 1, y: 1 },
+            &Size { w: 5, h: 4 },
+            GRAYSCALE,
+            2,
+        );
+        assert_eq!(
+            dst,
+            [0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 0, 1, 1, 2, 2, 0, 0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{Rect, Size, stride::Stride};
+
+/// Detect whether `curr` is `prev` scrolled vertically, e.g. so that a terminal or remote-display backend can send a scroll command instead of a full repaint.
+///
+/// `prev` and `curr` are flat byte slices of images of size `size`.
+///
+/// Returns `None` if no scroll was found. Otherwise, returns the scroll distance `dy` in pixels
+/// (positive if the content moved down, i.e. `curr` row `y` equals `prev` row `y - dy`)
+/// and the full-width areas of `curr` that still have to be redrawn after scrolling.
+/// If two distances are equally likely, the shorter one is returned, and then the positive one.
+pub fn detect_scroll(
+    prev: &[u8],
+    curr: &[u8],
+    size: &Size,
+    stride: Stride,
+) -> Option<(isize, Vec<Rect>)> {
+    let row_len = size.w * stride;
+    if row_len == 0 || size.h < 2 {
+        return None;
+    }
+    let prev_rows = prev[..row_len * size.h]
+        .chunks_exact(row_len)
+        .collect::<Vec<&[u8]>>();
+    let curr_rows = curr[..row_len * size.h]
+        .chunks_exact(row_len)
+        .collect::<Vec<&[u8]>>();
+    // Index the rows of `prev` by hash. Repeated rows, e.g. blank lines, are ambiguous, so they don't vote.
+    let mut prev_hashes: HashMap<u64, Option<usize>> = HashMap::new();
+    prev_rows.iter().enumerate().for_each(|(y, row)| {
+        prev_hashes
+            .entry(hash_row(row))
+            .and_modify(|unique| *unique = None)
+            .or_insert(Some(y));
+    });
+    // Each row of `curr` that matches a unique row of `prev` votes for a scroll distance.
+    let mut votes: HashMap<isize, usize> = HashMap::new();
+    curr_rows.iter().enumerate().for_each(|(y, row)| {
+        if let Some(Some(prev_y)) = prev_hashes.get(&hash_row(row))
+            && prev_rows[*prev_y] == *row
+        {
+            *votes
+                .entry(y.cast_signed() - prev_y.cast_signed())
+                .or_default() += 1;
+        }
+    });
+    // Break ties explicitly, because the iteration order of a `HashMap` is random.
+    let (dy, count) = votes
+        .into_iter()
+        .max_by_key(|(dy, count)| (*count, -dy.abs(), *dy))?;
+    let unchanged = prev_rows
+        .iter()
+        .zip(&curr_rows)
+        .filter(|(prev, curr)| prev == curr)
+        .count();
+    if dy == 0 || count <= unchanged {
+        return None;
+    }
+    // Group the rows that don't match after scrolling into rects.
+    let mut dirty: Vec<Rect> = Vec::new();
+    curr_rows.iter().enumerate().for_each(|(y, row)| {
+        let prev_y = y.cast_signed() - dy;
+        let matches = prev_y >= 0
+            && prev_y < size.h.cast_signed()
+            && prev_rows[prev_y.cast_unsigned()] == *row;
+        if !matches {
+            match dirty.last_mut() {
+                Some(rect) if rect.bottom() == y => rect.size.h += 1,
+                _ => dirty.push(Rect::new(0, y, size.w, 1)),
+            }
+        }
+    });
+    Some((dy, dirty))
+}
+
+fn hash_row(row: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    row.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_detect_scroll() {
+        let size = Size { w: 2, h: 5 };
+        let prev = [1, 1, 2, 2, 3, 3, 4, 4, 5, 5];
+        // Scrolled up by two rows, with two new rows at the bottom.
+        let curr = [3, 3, 4, 4, 5, 5, 6, 6, 7, 7];
+        assert_eq!(
+            detect_scroll(&prev, &curr, &size, GRAYSCALE),
+            Some((-2, vec![Rect::new(0, 3, 2, 2)]))
+        );
+        assert_eq!(detect_scroll(&prev, &prev, &size, GRAYSCALE), None);
+
+        // The row of 2s appears both one row up and one row down, so the tie goes to the positive distance.
+        let size = Size { w: 1, h: 3 };
+        (0..16).for_each(|_| {
+            assert_eq!(
+                detect_scroll(&[1, 2, 3], &[2, 9, 2], &size, GRAYSCALE),
+                Some((1, vec![Rect::new(0, 0, 1, 2)]))
+            );
+        });
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{Rect, Size, label_components, stride::Stride};
+
+/// How [`slice_sheet`] tells the background of a sprite sheet apart from the sprites.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SheetBackground<'a> {
+    /// Pixels whose last channel (alpha) is zero are background.
+    Transparent,
+    /// Pixels that equal this color are background. Its length is the per-pixel stride.
+    KeyColor(&'a [u8]),
+}
+
+/// Find the individual sprites in the sprite sheet `src`, an image of size `size`, and return their bounding rects.
+///
+/// Sprites are the 8-connected regions of pixels that aren't `background`. See [`label_components`].
+/// The rects are in reading order of each sprite's top-left-most pixel.
+pub fn slice_sheet(
+    src: &[u8],
+    size: &Size,
+    stride: Stride,
+    background: SheetBackground,
+) -> Vec<Rect> {
+    let mask = src
+        .chunks_exact(stride.get())
+        .take(size.w * size.h)
+        .map(|pixel| {
+            let is_background = match background {
+                SheetBackground::Transparent => pixel[stride.get() - 1] == 0,
+                SheetBackground::KeyColor(color) => pixel == color,
+            };
+            u8::from(!is_background)
+        })
+        .collect::<Vec<u8>>();
+    label_components(&mask, size)
+        .1
+        .into_iter()
+        .map(|component| component.rect)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_slice_sheet() {
+        let key = [255, 0, 255];
+        let sprite = [10, 20, 30];
+        let size = Size { w: 4, h: 2 };
+        // Two sprites separated by a column of the key color.
+        let src = [
+            sprite, key, sprite, sprite, //
+            sprite, key, key, sprite,
+        ]
+        .concat();
+        assert_eq!(
+            slice_sheet(&src, &size, RGB, SheetBackground::KeyColor(&key)),
+            [Rect::new(0, 0, 1, 2), Rect::new(2, 0, 2, 2)]
+        );
+    }
+}
@@ -1,13 +1,136 @@
+use std::fmt;
+
+use crate::{PositionI, Rect, clip, stride::Stride};
+
 /// Rectangular bounds defined by a width and height.
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Size {
     pub w: usize,
     pub h: usize,
 }
 
 impl Size {
-    pub const fn new(buffer: &[u8], w: usize, stride: usize) -> Self {
-        let h = (buffer.len() / stride) / w;
+    #[deprecated(
+        since = "0.3.0",
+        note = "silently truncates buffers whose length isn't a multiple of `w * stride`; use `Size::try_from_buffer` instead"
+    )]
+    pub const fn new(buffer: &[u8], w: usize, stride: Stride) -> Self {
+        let h = (buffer.len() / stride.get()) / w;
         Self { w, h }
     }
+
+    /// Derive a `Size` from the length of a buffer `len` bytes long, given the width `w` and the per-pixel `stride`.
+    ///
+    /// Returns an error if `len` isn't exactly a whole number of rows.
+    pub const fn try_from_buffer(len: usize, w: usize, stride: Stride) -> Result<Self, SizeError> {
+        if w == 0 {
+            Err(SizeError::ZeroWidth)
+        } else {
+            let row_len = w * stride.get();
+            if len.is_multiple_of(row_len) {
+                Ok(Self {
+                    w,
+                    h: len / row_len,
+                })
+            } else {
+                Err(SizeError::PartialRow { len, row_len })
+            }
+        }
+    }
+
+    /// Clip this size, placed at `position`, to `bounds`.
+    ///
+    /// Unlike clamping `w` and `h` to `bounds`, this accounts for `position`, including negative positions.
+    /// Returns the visible region within `bounds`, which is empty if nothing is visible. See [`crate::clip`].
+    pub fn clip_at(&self, position: &PositionI, bounds: &Size) -> Rect {
+        let clip = clip(position, bounds, self);
+        Rect {
+            position: clip.dst_position,
+            size: clip.size,
+        }
+    }
+
+    /// The length in bytes of a buffer with this size and the per-pixel `stride`.
+    pub const fn expected_len(&self, stride: Stride) -> usize {
+        self.w * self.h * stride.get()
+    }
+
+    /// The length in bytes of a buffer with this size and the per-pixel `stride`, or `None` if it overflows `usize`.
+    pub const fn checked_len(&self, stride: Stride) -> Option<usize> {
+        match self.w.checked_mul(self.h) {
+            Some(area) => area.checked_mul(stride.get()),
+            None => None,
+        }
+    }
+}
+
+/// An error returned by [`Size::try_from_buffer`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SizeError {
+    /// The width is zero, so the height can't be derived.
+    ZeroWidth,
+    /// The buffer length `len` isn't a multiple of the length of a row, `row_len`.
+    PartialRow { len: usize, row_len: usize },
+}
+
+impl fmt::Display for SizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroWidth => write!(f, "The width is zero"),
+            Self::PartialRow { len, row_len } => write!(
+                f,
+                "The buffer length {len} isn't a multiple of the row length {row_len}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SizeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_try_from_buffer() {
+        let size = Size::try_from_buffer(32 * 17 * RGB, 32, RGB).unwrap();
+        assert_eq!(size.h, 17);
+        assert_eq!(size.expected_len(RGB), 32 * 17 * RGB);
+        assert_eq!(size.checked_len(RGB), Some(32 * 17 * RGB));
+        assert_eq!(
+            Size {
+                w: usize::MAX / 2,
+                h: 3
+            }
+            .checked_len(RGB),
+            None
+        );
+        assert_eq!(
+            Size::try_from_buffer(32 * 17 * RGB + 1, 32, RGB).err(),
+            Some(SizeError::PartialRow {
+                len: 32 * 17 * RGB + 1,
+                row_len: 32 * RGB
+            })
+        );
+        assert_eq!(
+            Size::try_from_buffer(0, 0, RGB).err(),
+            Some(SizeError::ZeroWidth)
+        );
+    }
+
+    #[test]
+    fn test_clip_at() {
+        let size = Size { w: 10, h: 10 };
+        let bounds = Size { w: 16, h: 16 };
+        assert_eq!(
+            size.clip_at(&PositionI { x: 12, y: -4 }, &bounds),
+            Rect::new(12, 0, 4, 6)
+        );
+        assert!(
+            size.clip_at(&PositionI { x: -10, y: 0 }, &bounds)
+                .is_empty()
+        );
+        assert!(size.clip_at(&PositionI { x: 16, y: 0 }, &bounds).is_empty());
+    }
 }
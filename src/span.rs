@@ -0,0 +1,165 @@
+use crate::{
+    Rect, Size,
+    blend::source_over,
+    get_index,
+    stride::{RGBA, Stride},
+};
+
+/// A horizontal run of pixels on row `y`, from `x0` (inclusive) to `x1` (exclusive).
+///
+/// This is the usual output of a scanline rasterizer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub y: usize,
+    pub x0: usize,
+    pub x1: usize,
+}
+
+/// A horizontal run of pixels like [`Span`] that is partially covered, e.g. along the antialiased edge of a shape.
+///
+/// `coverage` ranges from 0 (not covered) to 255 (fully covered).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanAa {
+    pub y: usize,
+    pub x0: usize,
+    pub x1: usize,
+    pub coverage: u8,
+}
+
+/// Fill each span in `spans` with `color`.
+///
+/// - `dst` is a flat byte slice of an image of size `dst_size`.
+/// - `color` is a single pixel. Its length is the per-pixel stride.
+///
+/// Spans are clipped to `dst_size`.
+pub fn fill_spans(dst: &mut [u8], dst_size: &Size, spans: &[Span], color: &[u8]) {
+    let Some(stride) = Stride::new(color.len()) else {
+        return;
+    };
+    spans.iter().for_each(|span| {
+        let x1 = span.x1.min(dst_size.w);
+        if span.y < dst_size.h && span.x0 < x1 {
+            let index = get_index(span.x0, span.y, dst_size.w, stride);
+            fill_row(&mut dst[index..index + (x1 - span.x0) * stride], color);
+        }
+    });
+}
+
+/// Fill `rect` in `dst` with `color`.
+///
+/// - `dst` is a flat byte slice of an image of size `dst_size`.
+/// - `color` is a single pixel. Its length is the per-pixel stride.
+///
+/// `rect` is clipped to `dst_size`.
+pub fn fill_rect(dst: &mut [u8], dst_size: &Size, rect: &Rect, color: &[u8]) {
+    let Some(stride) = Stride::new(color.len()) else {
+        return;
+    };
+    let x1 = rect.right().min(dst_size.w);
+    let y1 = rect.bottom().min(dst_size.h);
+    if rect.position.x < x1 {
+        (rect.position.y..y1).for_each(|y| {
+            let index = get_index(rect.position.x, y, dst_size.w, stride);
+            fill_row(
+                &mut dst[index..index + (x1 - rect.position.x) * stride],
+                color,
+            );
+        });
+    }
+}
+
+/// Blend `color` onto each span in `spans`, scaling `color`'s alpha by each span's coverage.
+///
+/// - `dst` is a flat byte slice of an RGBA image of size `dst_size`.
+/// - `color` is a straight (not premultiplied) RGBA pixel.
+///
+/// Spans are clipped to `dst_size`.
+pub fn fill_spans_aa(dst: &mut [u8], dst_size: &Size, spans: &[SpanAa], color: &[u8; 4]) {
+    spans.iter().for_each(|span| {
+        let x1 = span.x1.min(dst_size.w);
+        if span.y < dst_size.h && span.x0 < x1 {
+            let index = get_index(span.x0, span.y, dst_size.w, RGBA);
+            dst[index..index + (x1 - span.x0) * RGBA]
+                .chunks_exact_mut(RGBA.get())
+                .for_each(|pixel| source_over(color, pixel, span.coverage));
+        }
+    });
+}
+
+/// Fill `row` with copies of `color`.
+/// Each copy doubles the length of the filled region, so this is a handful of `memcpy` calls instead of one per pixel.
+/// If `color` is empty, `row` is unchanged.
+pub(crate) fn fill_row(row: &mut [u8], color: &[u8]) {
+    if row.is_empty() || color.is_empty() {
+        return;
+    }
+    row[..color.len()].copy_from_slice(color);
+    let mut filled = color.len();
+    while filled < row.len() {
+        let n = filled.min(row.len() - filled);
+        row.copy_within(..n, filled);
+        filled += n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_fill_spans() {
+        let dst_size = Size { w: 8, h: 4 };
+        let mut dst = vec![0u8; 8 * 4 * RGB.get()];
+        let spans = [
+            Span { y: 0, x0: 1, x1: 4 },
+            Span {
+                y: 2,
+                x0: 5,
+                x1: 100,
+            },
+            Span { y: 9, x0: 0, x1: 8 },
+        ];
+        fill_spans(&mut dst, &dst_size, &spans, &[1, 2, 3]);
+        let pixel = |x: usize, y: usize| {
+            let index = get_index(x, y, dst_size.w, RGB);
+            &dst[index..index + RGB.get()]
+        };
+        assert_eq!(pixel(0, 0), [0, 0, 0]);
+        assert_eq!(pixel(1, 0), [1, 2, 3]);
+        assert_eq!(pixel(3, 0), [1, 2, 3]);
+        assert_eq!(pixel(4, 0), [0, 0, 0]);
+        assert_eq!(pixel(7, 2), [1, 2, 3]);
+        assert_eq!(dst.iter().filter(|c| **c == 1).count(), 6);
+    }
+
+    #[test]
+    fn test_fill_spans_aa() {
+        let dst_size = Size { w: 4, h: 1 };
+        let mut dst = vec![255u8; 4 * RGBA.get()];
+        let spans = [
+            SpanAa {
+                y: 0,
+                x0: 0,
+                x1: 1,
+                coverage: 255,
+            },
+            SpanAa {
+                y: 0,
+                x0: 1,
+                x1: 2,
+                coverage: 128,
+            },
+            SpanAa {
+                y: 0,
+                x0: 2,
+                x1: 3,
+                coverage: 0,
+            },
+        ];
+        fill_spans_aa(&mut dst, &dst_size, &spans, &[0, 0, 0, 255]);
+        assert_eq!(dst[0..4], [0, 0, 0, 255]);
+        assert_eq!(dst[4..8], [127, 127, 127, 255]);
+        assert_eq!(dst[8..16], [255; 8]);
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+use crate::{
+    Anchor, ClipResult, PositionI, Size,
+    blend::source_over,
+    clip,
+    perf::{self, Strategy},
+    stride::RGBA,
+};
+
+/// Where [`stamp`] places an overlay on the destination image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Placement {
+    /// Place the overlay's `anchor` point at the same anchor point of the destination image, moved `margin` pixels inwards,
+    /// e.g. `Anchor::BottomRight` for a watermark in the bottom-right corner.
+    Corner { anchor: Anchor, margin: usize },
+    /// Center the overlay.
+    Center,
+    /// Repeat the overlay over the whole destination image, starting at its top-left corner.
+    Tiled,
+}
+
+/// Blend the RGBA image `overlay` onto the RGBA image `dst` at `placement`, e.g. to watermark a batch of images.
+///
+/// - `opacity` scales the alpha of every overlay pixel. 255 is fully opaque.
+///
+/// The overlay is clipped to `dst`. With the `rayon` feature, rows are blended in parallel.
+pub fn stamp(
+    dst: &mut [u8],
+    dst_size: &Size,
+    overlay: &[u8],
+    overlay_size: &Size,
+    placement: Placement,
+    opacity: u8,
+) {
+    if overlay_size.w == 0 || overlay_size.h == 0 || dst_size.w == 0 {
+        return;
+    }
+    let clips = get_positions(dst_size, overlay_size, placement)
+        .iter()
+        .map(|position| clip(position, dst_size, overlay_size))
+        .filter(|clip| clip.size.w > 0 && clip.size.h > 0)
+        .collect::<Vec<ClipResult>>();
+    // Group the clips into bands of tiles that share the same rows, top to bottom.
+    // The bands don't overlap, so each destination row only needs to blend the clips of the band that covers it.
+    let bands = clips
+        .chunk_by(|a, b| a.dst_position.y == b.dst_position.y)
+        .collect::<Vec<&[ClipResult]>>();
+    let stamp_row = |(y, row): (usize, &mut [u8])| {
+        let band = bands.partition_point(|band| band[0].dst_position.y + band[0].size.h <= y);
+        let Some(band) = bands.get(band).filter(|band| band[0].dst_position.y <= y) else {
+            return;
+        };
+        band.iter().for_each(|clip| {
+            let src_y = clip.src_offset.y + y - clip.dst_position.y;
+            let src_index = (src_y * overlay_size.w + clip.src_offset.x) * RGBA;
+            let dst_index = clip.dst_position.x * RGBA;
+            overlay[src_index..src_index + clip.size.w * RGBA]
+                .chunks_exact(RGBA.get())
+                .zip(row[dst_index..dst_index + clip.size.w * RGBA].chunks_exact_mut(RGBA.get()))
+                .for_each(|(src, dst)| source_over(src, dst, opacity));
+        });
+    };
+    let rows = &mut dst[..dst_size.w * dst_size.h * RGBA];
+    #[cfg(feature = "rayon")]
+    {
+        perf::record(Strategy::Threaded);
+        rows.par_chunks_mut(dst_size.w * RGBA)
+            .enumerate()
+            .for_each(stamp_row);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        perf::record(Strategy::Blend);
+        rows.chunks_mut(dst_size.w * RGBA)
+            .enumerate()
+            .for_each(stamp_row);
+    }
+}
+
+/// The top-left positions of each copy of the overlay.
+fn get_positions(dst_size: &Size, overlay_size: &Size, placement: Placement) -> Vec<PositionI> {
+    let (dw, dh) = (dst_size.w.cast_signed(), dst_size.h.cast_signed());
+    let (ow, oh) = (overlay_size.w.cast_signed(), overlay_size.h.cast_signed());
+    match placement {
+        Placement::Corner { anchor, margin } => {
+            let (x, y) = anchor.offset(dst_size);
+            let (ox, oy) = anchor.offset(overlay_size);
+            // Move towards the center of `dst` by `margin` on each axis that's on an edge.
+            let inset = |position: usize, len: usize| match position {
+                0 => margin.cast_signed(),
+                p if p == len => -margin.cast_signed(),
+                _ => 0,
+            };
+            vec![PositionI {
+                x: x.cast_signed() - ox.cast_signed() + inset(x, dst_size.w),
+                y: y.cast_signed() - oy.cast_signed() + inset(y, dst_size.h),
+            }]
+        }
+        Placement::Center => vec![PositionI {
+            x: (dw - ow) / 2,
+            y: (dh - oh) / 2,
+        }],
+        Placement::Tiled => (0..dh)
+            .step_by(overlay_size.h)
+            .flat_map(|y| {
+                (0..dw)
+                    .step_by(overlay_size.w)
+                    .map(move |x| PositionI { x, y })
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp() {
+        let overlay = [255, 0, 0, 255];
+        let overlay_size = Size { w: 1, h: 1 };
+        let dst_size = Size { w: 3, h: 2 };
+        let mut dst = [0u8; 3 * 2 * 4];
+        stamp(
+            &mut dst,
+            &dst_size,
+            &overlay,
+            &overlay_size,
+            Placement::Corner {
+                anchor: Anchor::BottomRight,
+                margin: 1,
+            },
+            255,
+        );
+        assert_eq!(dst[4..8], overlay);
+        assert_eq!(dst.iter().filter(|c| **c > 0).count(), 2);
+
+        let mut dst = [0u8; 3 * 2 * 4];
+        stamp(
+            &mut dst,
+            &dst_size,
+            &overlay,
+            &overlay_size,
+            Placement::Tiled,
+            128,
+        );
+        assert!(dst.chunks_exact(4).all(|pixel| pixel == [255, 0, 0, 128]));
+
+        // The bottom row of tiles is cropped.
+        let overlay = [255, 0, 0, 255, 0, 0, 255, 255];
+        let overlay_size = Size { w: 1, h: 2 };
+        let dst_size = Size { w: 2, h: 3 };
+        let mut dst = [0u8; 2 * 3 * 4];
+        stamp(
+            &mut dst,
+            &dst_size,
+            &overlay,
+            &overlay_size,
+            Placement::Tiled,
+            255,
+        );
+        assert_eq!(dst[..8], [255, 0, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(dst[8..16], [0, 0, 255, 255, 0, 0, 255, 255]);
+        assert_eq!(dst[16..], [255, 0, 0, 255, 255, 0, 0, 255]);
+    }
+}
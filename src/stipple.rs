@@ -0,0 +1,118 @@
+use crate::{PositionU, Size, get_index, stride::Stride};
+
+/// How [`blit_stippled`] maps a destination pixel to a bit of its pattern.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StippleMode {
+    /// Bit `(x + y + phase) % 32`. The pattern shifts by one pixel per row, so `0x5555_5555` is a checkerboard.
+    #[default]
+    Diagonal,
+    /// Bit `(x + phase) % 32`, the same on every row, e.g. for dashed horizontal lines.
+    Horizontal,
+    /// Bit `(y + phase) % 32`, so whole rows are written or skipped, e.g. for dashed vertical lines or scanlines.
+    Vertical,
+}
+
+impl StippleMode {
+    const fn bit(&self, x: usize, y: usize, phase: usize) -> usize {
+        match self {
+            Self::Diagonal => (x + y + phase) % 32,
+            Self::Horizontal => (x + phase) % 32,
+            Self::Vertical => (y + phase) % 32,
+        }
+    }
+}
+
+/// Blit `src` onto `dst`, writing only the pixels whose bit in the repeating 32-bit `pattern` is set.
+///
+/// Bits are looked up by destination coordinates, so the pattern stays fixed on screen wherever `src` is placed. See [`StippleMode`].
+/// Incrementing `phase` every frame makes a dashed outline crawl ("marching ants"),
+/// and a pattern with every other bit set is a cheap 50% dissolve.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_stippled(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    stride: Stride,
+    pattern: u32,
+    phase: usize,
+    mode: StippleMode,
+) {
+    (0..src_size.h).for_each(|src_y| {
+        let dst_y = dst_position.y + src_y;
+        if mode == StippleMode::Vertical && (pattern >> mode.bit(0, dst_y, phase)) & 1 == 0 {
+            return;
+        }
+        let src_index = get_index(0, src_y, src_size.w, stride);
+        let dst_index = get_index(dst_position.x, dst_y, dst_size.w, stride);
+        let src_row = &src[src_index..src_index + src_size.w * stride];
+        let dst_row = &mut dst[dst_index..dst_index + src_size.w * stride];
+        if mode == StippleMode::Vertical {
+            dst_row.copy_from_slice(src_row);
+        } else {
+            dst_row
+                .chunks_exact_mut(stride.get())
+                .zip(src_row.chunks_exact(stride.get()))
+                .enumerate()
+                .filter(|(src_x, _)| {
+                    (pattern >> mode.bit(dst_position.x + src_x, dst_y, phase)) & 1 == 1
+                })
+                .for_each(|(_, (d, s))| d.copy_from_slice(s));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_stippled() {
+        let size = Size { w: 4, h: 2 };
+        let src = [1; 8];
+        let mut dst = [0; 8];
+        blit_stippled(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            GRAYSCALE,
+            0x5555_5555,
+            0,
+            StippleMode::Diagonal,
+        );
+        assert_eq!(dst, [1, 0, 1, 0, 0, 1, 0, 1]);
+
+        // Marching ants: a dash of two, shifted by one.
+        let mut dst = [0; 8];
+        blit_stippled(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            GRAYSCALE,
+            0x3333_3333,
+            1,
+            StippleMode::Horizontal,
+        );
+        assert_eq!(dst, [1, 0, 0, 1, 1, 0, 0, 1]);
+
+        let mut dst = [0; 8];
+        blit_stippled(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            GRAYSCALE,
+            0b10,
+            0,
+            StippleMode::Vertical,
+        );
+        assert_eq!(dst, [0, 0, 0, 0, 1, 1, 1, 1]);
+    }
+}
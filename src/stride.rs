@@ -1,12 +1,91 @@
 //! Stride values for various types of pixels.
 
+use std::{
+    fmt,
+    num::{NonZeroUsize, TryFromIntError},
+    ops::Mul,
+};
+
+/// The per-pixel stride: the number of bytes per pixel.
+///
+/// This is a distinct type so that a width or a channel count can't accidentally be passed where a stride is expected.
+/// Use the constants in this module, or [`Stride::new`] for other pixel types.
+///
+/// A stride can be multiplied by a `usize` to get a length in bytes, e.g. `w * h * RGB`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Stride(NonZeroUsize);
+
+impl Stride {
+    /// A stride of `bytes` bytes per pixel. Returns `None` if `bytes` is zero.
+    pub const fn new(bytes: usize) -> Option<Self> {
+        match NonZeroUsize::new(bytes) {
+            Some(bytes) => Some(Self(bytes)),
+            None => None,
+        }
+    }
+
+    /// The number of bytes per pixel.
+    pub const fn get(self) -> usize {
+        self.0.get()
+    }
+}
+
+impl From<Stride> for usize {
+    fn from(value: Stride) -> Self {
+        value.get()
+    }
+}
+
+impl TryFrom<usize> for Stride {
+    type Error = TryFromIntError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        NonZeroUsize::try_from(value).map(Self)
+    }
+}
+
+impl Mul<usize> for Stride {
+    type Output = usize;
+
+    fn mul(self, rhs: usize) -> usize {
+        self.get() * rhs
+    }
+}
+
+impl Mul<Stride> for usize {
+    type Output = usize;
+
+    fn mul(self, rhs: Stride) -> usize {
+        self * rhs.get()
+    }
+}
+
+impl fmt::Display for Stride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+const fn stride(bytes: usize) -> Stride {
+    match Stride::new(bytes) {
+        Some(stride) => stride,
+        None => panic!("A stride can't be zero"),
+    }
+}
+
 /// A 1-byte channel.
-pub const GRAYSCALE: usize = 1;
+pub const GRAYSCALE: Stride = stride(1);
 /// Three 1-byte channels: red, green, blue.
-pub const RGB: usize = 3;
+pub const RGB: Stride = stride(3);
 /// Four 1-byte channels: red, green, blue, alpha.
-pub const RGBA: usize = 4;
+pub const RGBA: Stride = stride(4);
 /// Three 4-byte channels, each of which is a f32: red, green, blue.
-pub const RGB_F32: usize = 12;
+pub const RGB_F32: Stride = stride(12);
 /// Four 4-byte channels, each of which is a f32: red, green, blue, alpha.
-pub const RGBA_F32: usize = 16;
+pub const RGBA_F32: Stride = stride(16);
+/// A 2-byte little-endian packed pixel: 5 bits of red, 6 bits of green, 5 bits of blue.
+pub const RGB565: Stride = stride(2);
+/// A 2-byte little-endian packed pixel: 1 unused bit, then 5 bits each of red, green, blue.
+pub const RGB555: Stride = stride(2);
+/// A 2-byte little-endian packed pixel: 4 bits each of red, green, blue, alpha.
+pub const RGBA4444: Stride = stride(2);
@@ -0,0 +1,251 @@
+use crate::{
+    Anchor, Insets, PositionI, PositionU, Rect, Size, blit, blit_clip_result, clip, fill_rect,
+    resolve_position, scale::box_resample, stride::Stride,
+};
+
+/// Make a box-filtered thumbnail of `src` that fits within `max_size`.
+///
+/// - `stride` is the per-pixel stride of both `src` and the thumbnail.
+/// - If `keep_aspect` is true, the thumbnail has the same aspect ratio as `src`. Otherwise, it is stretched to `max_size`.
+/// - If `letterbox` is a color, the thumbnail is exactly `max_size` and any area that isn't covered by the image is filled with `letterbox`, centering the image.
+///
+/// Returns the thumbnail's pixels and size. Panics if `letterbox` is a color whose length isn't `stride`.
+pub fn make_thumbnail(
+    src: &[u8],
+    src_size: &Size,
+    stride: Stride,
+    max_size: &Size,
+    keep_aspect: bool,
+    letterbox: Option<&[u8]>,
+) -> (Vec<u8>, Size) {
+    if let Some(color) = letterbox {
+        assert_eq!(
+            color.len(),
+            stride.get(),
+            "The letterbox color must be one pixel long"
+        );
+    }
+    let image_size = if keep_aspect {
+        fit_size(src_size, max_size)
+    } else {
+        *max_size
+    };
+    let mut image = vec![0; image_size.w * image_size.h * stride];
+    box_resample(src, src_size, &mut image, &image_size, stride);
+    match letterbox {
+        Some(color) => {
+            let mut thumbnail = vec![0; max_size.w * max_size.h * stride];
+            fill_rect(
+                &mut thumbnail,
+                max_size,
+                &Rect {
+                    position: PositionU::default(),
+                    size: *max_size,
+                },
+                color,
+            );
+            let position = PositionU {
+                x: (max_size.w - image_size.w) / 2,
+                y: (max_size.h - image_size.h) / 2,
+            };
+            blit(
+                &image,
+                &image_size,
+                &mut thumbnail,
+                &position,
+                max_size,
+                stride,
+            );
+            (thumbnail, *max_size)
+        }
+        None => (image, image_size),
+    }
+}
+
+/// A solid border drawn around a picture-in-picture overlay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PipBorder<'a> {
+    /// The border width in pixels.
+    pub width: usize,
+    /// A single pixel. Its length is the per-pixel stride.
+    pub color: &'a [u8],
+}
+
+/// Downscale `src` and blit it as a picture-in-picture overlay in a corner of `dst`, e.g. for a minimap or preview.
+///
+/// - `corner` is where the overlay is placed: e.g. [`Anchor::BottomRight`] places it in the bottom-right corner. [`Anchor::Center`] centers it.
+/// - `scale` is the size of the overlay relative to `src`. The overlay is shrunk further if needed to fit inside `dst`.
+/// - `margin` is the gap in pixels between the edge of `dst` and the overlay's border.
+///
+/// If `border`'s color isn't one pixel long, `dst` is unchanged.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_pip(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_size: &Size,
+    stride: Stride,
+    corner: Anchor,
+    scale: f32,
+    margin: usize,
+    border: Option<&PipBorder>,
+) {
+    if border.is_some_and(|border| border.color.len() != stride.get()) {
+        return;
+    }
+    let border_width = border.map_or(0, |border| border.width);
+    let area = Rect {
+        position: PositionU::default(),
+        size: *dst_size,
+    }
+    .inset(&Insets::uniform(margin + border_width));
+    let scaled_size = Size {
+        w: ((src_size.w as f32 * scale).round() as usize).max(1),
+        h: ((src_size.h as f32 * scale).round() as usize).max(1),
+    };
+    let max_size = if scaled_size.w <= area.size.w && scaled_size.h <= area.size.h {
+        scaled_size
+    } else {
+        fit_size(&scaled_size, &area.size)
+    };
+    let (image, image_size) = make_thumbnail(src, src_size, stride, &max_size, false, None);
+    if image_size.w == 0 || image_size.h == 0 {
+        return;
+    }
+    let (x, y) = corner.offset(&area.size);
+    let target = PositionI {
+        x: (area.position.x + x).cast_signed(),
+        y: (area.position.y + y).cast_signed(),
+    };
+    let position = resolve_position(corner, &image_size, &target);
+    if let Some(border) = border {
+        let width = border.width.cast_signed();
+        let border_size = Size {
+            w: image_size.w + border.width * 2,
+            h: image_size.h + border.width * 2,
+        };
+        let border_position = PositionI {
+            x: position.x - width,
+            y: position.y - width,
+        };
+        let border_clip = clip(&border_position, dst_size, &border_size);
+        fill_rect(
+            dst,
+            dst_size,
+            &Rect {
+                position: border_clip.dst_position,
+                size: border_clip.size,
+            },
+            border.color,
+        );
+    }
+    let clip = clip(&position, dst_size, &image_size);
+    blit_clip_result(&image, &image_size, dst, dst_size, &clip, stride);
+}
+
+/// Returns the largest size with the same aspect ratio as `size` that fits within `max_size`.
+pub(crate) fn fit_size(size: &Size, max_size: &Size) -> Size {
+    if size.w == 0 || size.h == 0 {
+        Size::default()
+    } else if size.w * max_size.h > size.h * max_size.w {
+        // Wider than the bounds: fit to width.
+        Size {
+            w: max_size.w,
+            h: (max_size.w * size.h / size.w).max(1).min(max_size.h),
+        }
+    } else {
+        Size {
+            w: (max_size.h * size.w / size.h).max(1).min(max_size.w),
+            h: max_size.h,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGB};
+
+    #[test]
+    fn test_make_thumbnail() {
+        // A 4x2 image: the left half is black and the right half is white.
+        let src = [0, 0, 255, 255, 0, 0, 255, 255];
+        let src_size = Size { w: 4, h: 2 };
+        let (thumbnail, size) =
+            make_thumbnail(&src, &src_size, GRAYSCALE, &Size { w: 2, h: 2 }, true, None);
+        assert_eq!(size, Size { w: 2, h: 1 });
+        assert_eq!(thumbnail, [0, 255]);
+
+        let (thumbnail, size) = make_thumbnail(
+            &src,
+            &src_size,
+            GRAYSCALE,
+            &Size { w: 2, h: 3 },
+            true,
+            Some(&[7]),
+        );
+        assert_eq!(size, Size { w: 2, h: 3 });
+        assert_eq!(thumbnail, [7, 7, 0, 255, 7, 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_make_thumbnail_letterbox_len() {
+        make_thumbnail(
+            &[0; 4],
+            &Size { w: 2, h: 2 },
+            GRAYSCALE,
+            &Size { w: 2, h: 3 },
+            true,
+            Some(&[]),
+        );
+    }
+
+    #[test]
+    fn test_blit_pip() {
+        let src = [255u8; 8 * 8 * RGB.get()];
+        let src_size = Size { w: 8, h: 8 };
+        let dst_size = Size { w: 16, h: 16 };
+        let mut dst = vec![0u8; 16 * 16 * RGB.get()];
+        let border = PipBorder {
+            width: 1,
+            color: &[1, 2, 3],
+        };
+        blit_pip(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            RGB,
+            Anchor::BottomRight,
+            0.5,
+            2,
+            Some(&border),
+        );
+        let pixel = |x: usize, y: usize| &dst[(x + y * 16) * RGB..(x + y * 16 + 1) * RGB];
+        // The overlay is 4x4, ending 3 pixels from the bottom-right corner.
+        assert_eq!(pixel(12, 12), [255, 255, 255]);
+        assert_eq!(pixel(9, 9), [255, 255, 255]);
+        assert_eq!(pixel(8, 8), [1, 2, 3]);
+        assert_eq!(pixel(13, 13), [1, 2, 3]);
+        assert_eq!(pixel(14, 14), [0, 0, 0]);
+
+        // An empty border color is rejected.
+        let mut dst = vec![0u8; 16 * 16 * RGB.get()];
+        blit_pip(
+            &src,
+            &src_size,
+            &mut dst,
+            &dst_size,
+            RGB,
+            Anchor::BottomRight,
+            0.5,
+            2,
+            Some(&PipBorder {
+                width: 1,
+                color: &[],
+            }),
+        );
+        assert!(dst.iter().all(|c| *c == 0));
+    }
+}
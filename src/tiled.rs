@@ -0,0 +1,57 @@
+use crate::{Rect, Size, get_index, stride::Stride};
+
+/// Fill `dst_rect` in `dst` by repeating `src`, e.g. for a tiled background or UI texture.
+///
+/// The first tile's top-left corner is at the top-left corner of `dst_rect`.
+/// Tiles along the right and bottom edges are cut off where `dst_rect` ends. `dst_rect` is clipped to `dst_size`.
+pub fn blit_tiled(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_rect: &Rect,
+    dst_size: &Size,
+    stride: Stride,
+) {
+    if src_size.w == 0 || src_size.h == 0 {
+        return;
+    }
+    let x1 = dst_rect.right().min(dst_size.w);
+    let y1 = dst_rect.bottom().min(dst_size.h);
+    if x1 <= dst_rect.position.x || y1 <= dst_rect.position.y {
+        return;
+    }
+    let row_len = (x1 - dst_rect.position.x) * stride;
+    let src_w_stride = src_size.w * stride;
+    (dst_rect.position.y..y1).for_each(|y| {
+        let src_y = (y - dst_rect.position.y) % src_size.h;
+        let src_index = get_index(0, src_y, src_size.w, stride);
+        let src_row = &src[src_index..src_index + src_w_stride];
+        let dst_index = get_index(dst_rect.position.x, y, dst_size.w, stride);
+        dst[dst_index..dst_index + row_len]
+            .chunks_mut(src_w_stride)
+            .for_each(|tile| tile.copy_from_slice(&src_row[..tile.len()]));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::GRAYSCALE;
+
+    #[test]
+    fn test_blit_tiled() {
+        // 1 2
+        // 3 4
+        let src = [1, 2, 3, 4];
+        let mut dst = [0; 16];
+        blit_tiled(
+            &src,
+            &Size { w: 2, h: 2 },
+            &mut dst,
+            &Rect::new(1, 0, 3, 3),
+            &Size { w: 4, h: 4 },
+            GRAYSCALE,
+        );
+        assert_eq!(dst, [0, 1, 2, 1, 0, 3, 4, 3, 0, 1, 2, 1, 0, 0, 0, 0]);
+    }
+}
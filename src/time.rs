@@ -0,0 +1,148 @@
+//! Frame pacing for blit-present loops.
+
+use std::{
+    hint::spin_loop,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// The default amount of time before a frame deadline that [`FramePacer`] stops sleeping and starts spinning.
+///
+/// OS sleeps routinely overshoot by a millisecond or more, so the last stretch is spent spinning instead.
+pub const DEFAULT_SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Frame-time statistics gathered by a [`FramePacer`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameStats {
+    /// The number of frames that have been paced.
+    pub frames: u64,
+    /// The duration of the most recent frame.
+    pub last: Duration,
+    /// The shortest frame.
+    pub min: Duration,
+    /// The longest frame.
+    pub max: Duration,
+    /// The sum of every frame's duration.
+    pub total: Duration,
+}
+
+impl FrameStats {
+    /// The mean frame duration.
+    pub fn average(&self) -> Duration {
+        if self.frames == 0 {
+            Duration::ZERO
+        } else {
+            self.total.div_f64(self.frames as f64)
+        }
+    }
+
+    /// The mean frames per second.
+    pub fn fps(&self) -> f64 {
+        let average = self.average().as_secs_f64();
+        if average > 0. { 1. / average } else { 0. }
+    }
+}
+
+/// Limits a render loop to a target frame rate using a hybrid of sleeping and spinning.
+///
+/// Call [`FramePacer::wait`] once per frame, after presenting.
+/// Deadlines are scheduled on a fixed cadence so that small oversleeps don't accumulate into drift.
+/// If a frame runs more than one frame late, the cadence resets rather than rushing to catch up.
+pub struct FramePacer {
+    /// The target duration of each frame.
+    pub frame_duration: Duration,
+    /// How long before each deadline to stop sleeping and start spinning. See [`DEFAULT_SPIN_THRESHOLD`].
+    pub spin_threshold: Duration,
+    deadline: Instant,
+    previous: Instant,
+    stats: FrameStats,
+}
+
+impl FramePacer {
+    /// Create a pacer that targets `fps` frames per second. Panics if `fps` isn't positive.
+    pub fn new(fps: f64) -> Self {
+        assert!(fps > 0., "The target frame rate must be positive, not {fps}");
+        let now = Instant::now();
+        let frame_duration = Duration::from_secs_f64(1. / fps);
+        Self {
+            frame_duration,
+            spin_threshold: DEFAULT_SPIN_THRESHOLD,
+            deadline: now + frame_duration,
+            previous: now,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Block until the end of the current frame. Returns the duration of the frame that just ended.
+    pub fn wait(&mut self) -> Duration {
+        let now = Instant::now();
+        if now < self.deadline {
+            let remaining = self.deadline - now;
+            if remaining > self.spin_threshold {
+                sleep(remaining - self.spin_threshold);
+            }
+            while Instant::now() < self.deadline {
+                spin_loop();
+            }
+        }
+
+        let now = Instant::now();
+        // Reset the cadence if we're more than a frame behind.
+        self.deadline = if now > self.deadline + self.frame_duration {
+            now + self.frame_duration
+        } else {
+            self.deadline + self.frame_duration
+        };
+
+        let frame = now - self.previous;
+        self.previous = now;
+        self.record(frame);
+        frame
+    }
+
+    /// Frame-time statistics since creation or the last call to [`FramePacer::reset_stats`].
+    pub const fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Clear the frame-time statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = FrameStats::default();
+    }
+
+    fn record(&mut self, frame: Duration) {
+        let stats = &mut self.stats;
+        if stats.frames == 0 {
+            stats.min = frame;
+            stats.max = frame;
+        } else {
+            stats.min = stats.min.min(frame);
+            stats.max = stats.max.max(frame);
+        }
+        stats.frames += 1;
+        stats.last = frame;
+        stats.total += frame;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_pacer() {
+        let mut pacer = FramePacer::new(500.);
+        let start = Instant::now();
+        (0..5).for_each(|_| {
+            pacer.wait();
+        });
+        assert!(start.elapsed() >= pacer.frame_duration * 4);
+        assert_eq!(pacer.stats().frames, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be positive")]
+    fn test_frame_pacer_zero_fps() {
+        FramePacer::new(0.);
+    }
+}
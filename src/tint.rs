@@ -0,0 +1,76 @@
+use crate::{
+    BlendMode, PositionU, Size,
+    blend::{mul_div_255, source_over},
+    blit_mode,
+    stride::RGBA,
+};
+
+/// Blit the RGBA image `src` onto the RGBA image `dst`, multiplying each source pixel by the RGBA color `tint` on the fly.
+///
+/// This is like SDL's color and alpha modulation: white source pixels become `tint`, so one white sprite template can be drawn in any color
+/// without keeping tinted copies in memory. The tinted pixels are then blended like [`crate::blit_blend`].
+/// `[255, 255, 255, 255]` doesn't change the source, and a lower alpha fades it.
+pub fn blit_tinted(
+    src: &[u8],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    tint: [u8; 4],
+) {
+    blit_mode(
+        src,
+        src_size,
+        dst,
+        dst_position,
+        dst_size,
+        RGBA,
+        &Tinted { tint },
+    );
+}
+
+/// Source-over blending of each source pixel multiplied by `tint`.
+struct Tinted {
+    tint: [u8; 4],
+}
+
+impl BlendMode for Tinted {
+    #[inline]
+    fn blend(&self, src: &[u8], dst: &mut [u8]) {
+        let tinted: [u8; 4] = std::array::from_fn(|i| mul_div_255(src[i], self.tint[i]));
+        source_over(&tinted, dst, 255);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blit_tinted() {
+        let src = [255, 255, 255, 255, 255, 255, 255, 0];
+        let size = Size { w: 2, h: 1 };
+        let mut dst = [0, 0, 0, 255, 10, 20, 30, 255];
+        blit_tinted(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            [255, 128, 0, 255],
+        );
+        assert_eq!(dst, [255, 128, 0, 255, 10, 20, 30, 255]);
+
+        // Half alpha.
+        let mut dst = [0, 0, 0, 255, 10, 20, 30, 255];
+        blit_tinted(
+            &src,
+            &size,
+            &mut dst,
+            &PositionU::default(),
+            &size,
+            [255, 0, 0, 128],
+        );
+        assert_eq!(dst, [128, 0, 0, 255, 10, 20, 30, 255]);
+    }
+}
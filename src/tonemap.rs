@@ -0,0 +1,130 @@
+use crate::{
+    PositionU, Size,
+    color::{encode_index, encode_lut},
+    get_index,
+    stride::Stride,
+};
+
+/// A tone-mapping operator that compresses HDR values into `[0, 1]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Tonemap {
+    /// `x / (1 + x)`
+    #[default]
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve.
+    Aces,
+    /// Clamp to `[0, 1]`.
+    Clamp,
+}
+
+impl Tonemap {
+    /// Apply the operator to a linear value.
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.max(0.);
+        match self {
+            Self::Reinhard => x / (1. + x),
+            Self::Aces => ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).min(1.),
+            Self::Clamp => x.min(1.),
+        }
+    }
+}
+
+/// Blit a linear HDR `src` onto an 8-bit sRGB `dst`, tone-mapping and encoding each pixel as it's copied.
+///
+/// - `src` is a flat slice of f32 channels, e.g. from an image with [`crate::stride::RGBA_F32`]. Cast byte data with e.g. `bytemuck`.
+/// - `dst_stride` is the per-pixel stride of `dst`, which is also the number of channels per pixel in `src`: [`crate::stride::RGB`] or [`crate::stride::RGBA`].
+/// - `exposure` scales each color channel before tone mapping.
+///
+/// The alpha channel, if any, isn't tone-mapped or encoded. It is clamped to `[0, 1]` and scaled to `[0, 255]`.
+/// If `dst_stride` is less than 3, `dst` is unchanged.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_tonemap(
+    src: &[f32],
+    src_size: &Size,
+    dst: &mut [u8],
+    dst_position: &PositionU,
+    dst_size: &Size,
+    dst_stride: Stride,
+    operator: Tonemap,
+    exposure: f32,
+) {
+    if src_size.w > 0 && src_size.h > 0 && dst_stride.get() >= 3 {
+        let encode = encode_lut();
+        let src_w_stride = src_size.w * dst_stride;
+        (0..src_size.h).for_each(|src_y| {
+            let src_index = get_index(0, src_y, src_size.w, dst_stride);
+            let dst_index = get_index(
+                dst_position.x,
+                dst_position.y + src_y,
+                dst_size.w,
+                dst_stride,
+            );
+            src[src_index..src_index + src_w_stride]
+                .chunks_exact(dst_stride.get())
+                .zip(dst[dst_index..dst_index + src_w_stride].chunks_exact_mut(dst_stride.get()))
+                .for_each(|(src, dst)| {
+                    (0..3).for_each(|i| {
+                        let mapped = operator.apply(src[i] * exposure);
+                        dst[i] = encode[encode_index(mapped)];
+                    });
+                    if dst_stride.get() > 3 {
+                        dst[3] = (src[3].clamp(0., 1.) * 255. + 0.5) as u8;
+                    }
+                });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{GRAYSCALE, RGBA};
+
+    #[test]
+    fn test_blit_tonemap() {
+        let src = [0., 0.5, 1000., 2., -1., 1., 0.25, 0.5];
+        let src_size = Size { w: 2, h: 1 };
+        let mut dst = [0u8; 2 * RGBA.get()];
+        let dst_size = Size { w: 2, h: 1 };
+        blit_tonemap(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &dst_size,
+            RGBA,
+            Tonemap::Clamp,
+            1.,
+        );
+        assert_eq!(dst, [0, 188, 255, 255, 0, 255, 137, 128]);
+
+        blit_tonemap(
+            &src,
+            &src_size,
+            &mut dst,
+            &PositionU::default(),
+            &dst_size,
+            RGBA,
+            Tonemap::Reinhard,
+            1.,
+        );
+        // 1000 / 1001 rounds to white, but 0.5 / 1.5 is darker than 0.5.
+        assert_eq!(dst[2], 255);
+        assert!(dst[1] < 188);
+        assert!(Tonemap::Aces.apply(1000.) <= 1.);
+
+        // Grayscale isn't supported.
+        let mut gray = [7u8; 2];
+        blit_tonemap(
+            &src[..2],
+            &src_size,
+            &mut gray,
+            &PositionU::default(),
+            &dst_size,
+            GRAYSCALE,
+            Tonemap::Clamp,
+            1.,
+        );
+        assert_eq!(gray, [7, 7]);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{Size, get_index, stride::Stride};
+
+/// The width and height of the square blocks that are transposed at a time, in pixels.
+const BLOCK: usize = 16;
+
+/// Transpose `src`, an image of size `src_size`, into `dst`, an image of size `src_size.h` by `src_size.w`,
+/// i.e. pixel `(x, y)` of `src` becomes pixel `(y, x)` of `dst`.
+///
+/// This converts between row-major and column-major layouts, e.g. to display column-major scientific data.
+/// The image is copied in small square blocks so that reads and writes both stay in the cache, even for large images.
+pub fn transpose(src: &[u8], src_size: &Size, dst: &mut [u8], stride: Stride) {
+    for_each_blocked(src_size, |x, y| {
+        let src_index = get_index(x, y, src_size.w, stride);
+        let dst_index = get_index(y, x, src_size.h, stride);
+        dst[dst_index..dst_index + stride.get()]
+            .copy_from_slice(&src[src_index..src_index + stride.get()]);
+    });
+}
+
+/// Call `f(x, y)` for every pixel of an image of size `size`, visiting the pixels in square blocks.
+pub(crate) fn for_each_blocked<F: FnMut(usize, usize)>(size: &Size, mut f: F) {
+    (0..size.h).step_by(BLOCK).for_each(|block_y| {
+        (0..size.w).step_by(BLOCK).for_each(|block_x| {
+            (block_y..(block_y + BLOCK).min(size.h)).for_each(|y| {
+                (block_x..(block_x + BLOCK).min(size.w)).for_each(|x| f(x, y));
+            });
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::RGB;
+
+    #[test]
+    fn test_transpose() {
+        let size = Size { w: 37, h: 21 };
+        let src = (0..size.expected_len(RGB))
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+        let mut transposed = vec![0; src.len()];
+        transpose(&src, &size, &mut transposed, RGB);
+        assert_eq!(transposed[3..6], src[37 * 3..38 * 3]);
+        let mut restored = vec![0; src.len()];
+        transpose(&transposed, &Size { w: 21, h: 37 }, &mut restored, RGB);
+        assert_eq!(restored, src);
+    }
+}